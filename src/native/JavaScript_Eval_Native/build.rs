@@ -0,0 +1,10 @@
+fn main() {
+    // `v8` doesn't bind `v8::Context::AllowCodeGenerationFromStrings`, this calls it from C++ so that the
+    // symbol is named by the compiler for the target rather than by hand.
+    cc::Build::new()
+        .cpp(true)
+        .file("src/code_generation.cc")
+        .compile("code_generation");
+
+    println!("cargo:rerun-if-changed=src/code_generation.cc");
+}
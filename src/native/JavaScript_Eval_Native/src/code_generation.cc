@@ -0,0 +1,17 @@
+// Only what's called below of `v8::Context` as V8 11.0's v8-context.h declares it. The member is
+// resolved against the V8 library the `v8` crate links.
+namespace v8 {
+
+class Context {
+ public:
+  void AllowCodeGenerationFromStrings(bool allow);
+};
+
+}  // namespace v8
+
+// A `&v8::Context` on the Rust side is the same pointer as a `v8::Context*` here.
+extern "C" void javascript_eval_native__Context__AllowCodeGenerationFromStrings(
+    v8::Context* context,
+    bool allow) {
+  context->AllowCodeGenerationFromStrings(allow);
+}
@@ -0,0 +1,372 @@
+use std::{
+    convert::TryFrom,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::modules::{normalize, throw_error};
+
+// `require` for CommonJS modules on disk, confined to a root directory. Scripts see module paths as if
+// the root were `/`, so `__filename` and stack traces don't give away where it really is.
+//
+// Like Node, a specifier is tried as a file, then with `.js` and `.json` added, then as a directory with
+// a `package.json` `main` or an `index` file. Bare specifiers are looked for in the `node_modules`
+// directories from the requiring module's directory up to the root. Every candidate is checked after
+// following symlinks, nothing outside the root is ever read.
+struct CommonJs {
+    root: Result<PathBuf, String>,
+}
+
+// Loaded modules by path, kept on the global behind a private key so scripts can't reach them.
+const CACHE_KEY: &str = "JavaScript.Eval.requireCache";
+
+const WRAPPER_START: &str = "(function (exports, require, module, __filename, __dirname) {";
+const WRAPPER_END: &str = "\n})";
+
+pub fn install(scope: &mut v8::HandleScope, root: &Path) -> Option<()> {
+    let root = root.canonicalize().map_err(|e| {
+        format!(
+            "The CommonJS root `{}` can't be used: {}",
+            root.display(),
+            e
+        )
+    });
+
+    scope.set_slot(CommonJs { root });
+
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let global = scope.get_current_context().global(scope);
+
+    let key = v8::String::new(scope, CACHE_KEY)?;
+    let key = v8::Private::for_api(scope, Some(key));
+    let cache = v8::Object::new(scope);
+
+    global.set_private(scope, key, cache.into())?;
+
+    let name = v8::String::new(scope, "require")?;
+    let require = new_require(scope, "/")?;
+
+    global.set(scope, name.into(), require.into())?;
+
+    Some(())
+}
+
+fn new_require<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    directory: &str,
+) -> Option<v8::Local<'s, v8::Function>> {
+    let directory = v8::String::new(scope, directory)?;
+
+    v8::Function::builder(require)
+        .data(directory.into())
+        .build(scope)
+}
+
+fn require(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let specifier = match v8::Local::<v8::String>::try_from(args.get(0)) {
+        Ok(specifier) => specifier.to_rust_string_lossy(scope),
+        Err(_) => return throw_error(scope, "The module specifier must be a string."),
+    };
+
+    // The directory of the module this `require` was handed to.
+    let directory = args.data().to_rust_string_lossy(scope);
+
+    let root = match scope
+        .get_slot::<CommonJs>()
+        .map(|commonjs| commonjs.root.clone())
+    {
+        Some(Ok(root)) => root,
+        Some(Err(error)) => return throw_error(scope, &error),
+        None => return,
+    };
+
+    let (filename, path) = match resolve(scope, &root, &directory, &specifier) {
+        Some(resolved) => resolved,
+        None => return throw_error(scope, &format!("Cannot find module '{}'", specifier)),
+    };
+
+    if let Some(exports) = load(scope, &filename, &path) {
+        rv.set(exports);
+    }
+}
+
+// The real path of `filename`, as long as it exists and is inside the root.
+fn real_path(root: &Path, filename: &str) -> Option<PathBuf> {
+    let path = root
+        .join(filename.trim_start_matches('/'))
+        .canonicalize()
+        .ok()?;
+
+    if path.starts_with(root) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+// The module's name as scripts see it and its real path.
+fn resolve(
+    scope: &mut v8::HandleScope,
+    root: &Path,
+    directory: &str,
+    specifier: &str,
+) -> Option<(String, PathBuf)> {
+    if specifier.starts_with('/') {
+        return resolve_file_or_directory(scope, root, specifier);
+    }
+
+    if specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+    {
+        return resolve_file_or_directory(scope, root, &format!("{}/{}", directory, specifier));
+    }
+
+    let mut directory = normalize(directory);
+
+    loop {
+        let candidate = format!(
+            "{}/node_modules/{}",
+            directory.trim_end_matches('/'),
+            specifier
+        );
+
+        if let Some(resolved) = resolve_file_or_directory(scope, root, &candidate) {
+            return Some(resolved);
+        }
+
+        if directory == "/" {
+            return None;
+        }
+
+        directory = normalize(&format!("{}/..", directory));
+    }
+}
+
+fn resolve_file_or_directory(
+    scope: &mut v8::HandleScope,
+    root: &Path,
+    filename: &str,
+) -> Option<(String, PathBuf)> {
+    let filename = normalize(filename);
+
+    if let Some(resolved) = resolve_file(root, &filename) {
+        return Some(resolved);
+    }
+
+    if !real_path(root, &filename)?.is_dir() {
+        return None;
+    }
+
+    if let Some(main) = package_main(scope, root, &filename) {
+        let main = normalize(&format!("{}/{}", filename, main));
+
+        if let Some(resolved) = resolve_file(root, &main).or_else(|| resolve_index(root, &main)) {
+            return Some(resolved);
+        }
+    }
+
+    resolve_index(root, &filename)
+}
+
+fn resolve_file(root: &Path, filename: &str) -> Option<(String, PathBuf)> {
+    first_file(
+        root,
+        &[
+            String::from(filename),
+            format!("{}.js", filename),
+            format!("{}.json", filename),
+        ],
+    )
+}
+
+fn resolve_index(root: &Path, directory: &str) -> Option<(String, PathBuf)> {
+    let directory = directory.trim_end_matches('/');
+
+    first_file(
+        root,
+        &[
+            format!("{}/index.js", directory),
+            format!("{}/index.json", directory),
+        ],
+    )
+}
+
+fn first_file(root: &Path, candidates: &[String]) -> Option<(String, PathBuf)> {
+    candidates.iter().find_map(|candidate| {
+        real_path(root, candidate)
+            .filter(|path| path.is_file())
+            .map(|path| (candidate.clone(), path))
+    })
+}
+
+fn package_main(scope: &mut v8::HandleScope, root: &Path, directory: &str) -> Option<String> {
+    let package = real_path(root, &format!("{}/package.json", directory))?;
+    let package = fs::read_to_string(package).ok()?;
+
+    let scope = &mut v8::HandleScope::new(scope);
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let package = v8::String::new(tc, &package)?;
+    let package = v8::json::parse(tc, package)?;
+    let package = v8::Local::<v8::Object>::try_from(package).ok()?;
+
+    let key = v8::String::new(tc, "main")?;
+    let main = package.get(tc, key.into())?;
+
+    if main.is_string() {
+        Some(main.to_rust_string_lossy(tc))
+    } else {
+        None
+    }
+}
+
+fn set(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+    name: &str,
+    value: v8::Local<v8::Value>,
+) -> Option<()> {
+    let name = v8::String::new(scope, name)?;
+
+    object.set(scope, name.into(), value)?;
+
+    Some(())
+}
+
+fn get<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<v8::Object>,
+    name: &str,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let name = v8::String::new(scope, name)?;
+
+    object.get(scope, name.into())
+}
+
+// Hands back the module's exports, or `None` with an exception on the scope.
+fn load<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    filename: &str,
+    path: &Path,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let key = v8::String::new(scope, CACHE_KEY)?;
+    let key = v8::Private::for_api(scope, Some(key));
+
+    let global = scope.get_current_context().global(scope);
+    let cache = global.get_private(scope, key)?;
+    let cache = v8::Local::<v8::Object>::try_from(cache).ok()?;
+
+    let cache_key = v8::String::new(scope, filename)?.into();
+
+    // A module that's still loading is in here too, requiring it from one of its dependencies hands
+    // back whatever it has exported so far, the same as in Node.
+    if let Ok(module) = v8::Local::<v8::Object>::try_from(cache.get(scope, cache_key)?) {
+        return get(scope, module, "exports");
+    }
+
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            throw_error(
+                scope,
+                &format!("Couldn't read module '{}': {}", filename, e),
+            );
+            return None;
+        }
+    };
+
+    let module = v8::Object::new(scope);
+    let exports = v8::Object::new(scope);
+
+    let name = v8::String::new(scope, filename)?;
+    let dirname = v8::String::new(scope, &normalize(&format!("{}/..", filename)))?;
+
+    set(scope, module, "id", name.into())?;
+    set(scope, module, "filename", name.into())?;
+    set(scope, module, "exports", exports.into())?;
+
+    if filename.ends_with(".json") {
+        let source = v8::String::new(scope, &source)?;
+        let json = v8::json::parse(scope, source)?;
+
+        set(scope, module, "exports", json)?;
+        cache.set(scope, cache_key, module.into())?;
+
+        return Some(json);
+    }
+
+    cache.set(scope, cache_key, module.into())?;
+
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let exports = run(tc, name, dirname, module, exports, &source);
+
+    match exports {
+        Some(exports) => Some(exports),
+        None => {
+            // So that requiring it again tries again, rather than handing back a half loaded module.
+            cache.delete(tc, cache_key);
+
+            tc.rethrow();
+
+            None
+        }
+    }
+}
+
+fn run<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    filename: v8::Local<'s, v8::String>,
+    dirname: v8::Local<v8::String>,
+    module: v8::Local<v8::Object>,
+    exports: v8::Local<v8::Object>,
+    source: &str,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let source = format!("{}{}{}", WRAPPER_START, source, WRAPPER_END);
+    let source = v8::String::new(scope, &source)?;
+
+    let source_map_url = v8::undefined(scope);
+
+    // The negative column offset keeps the columns on the first line what they are in the file.
+    let origin = v8::ScriptOrigin::new(
+        scope,
+        filename.into(),
+        0,
+        -(WRAPPER_START.len() as i32),
+        false,
+        0,
+        source_map_url.into(),
+        false,
+        false,
+        false,
+    );
+
+    let script = v8::Script::compile(scope, source, Some(&origin))?;
+    let function = v8::Local::<v8::Function>::try_from(script.run(scope)?).ok()?;
+
+    let directory = dirname.to_rust_string_lossy(scope);
+    let require = new_require(scope, &directory)?;
+
+    let arguments = [
+        exports.into(),
+        require.into(),
+        module.into(),
+        filename.into(),
+        dirname.into(),
+    ];
+
+    function.call(scope, exports.into(), &arguments)?;
+
+    let loaded = v8::Boolean::new(scope, true);
+
+    set(scope, module, "loaded", loaded.into())?;
+
+    get(scope, module, "exports")
+}
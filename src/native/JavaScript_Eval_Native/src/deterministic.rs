@@ -0,0 +1,268 @@
+use std::convert::TryFrom;
+
+use crate::v8facade::DeterministicOptions;
+
+// Makes the same inputs give the same outputs: `Math.random` is seeded, `Date` and timers read a clock
+// only the host moves, local time is UTC, and `Intl` and the `toLocale...` methods default to a fixed
+// locale. It's done in the context rather than with V8's flags, which are shared by every isolate in the
+// process and can only be set before V8 is initialized. A `--random-seed` would give every facade the
+// same sequence, deterministic or not, and no flag fixes the clock or the time zone of one context.
+struct Clock {
+    time: f64,
+}
+
+const MAKE_DETERMINISTIC: &str = r#"
+(function (now, seedLow, seedHigh, locale) {
+    'use strict';
+
+    const { apply, construct, defineProperty, getOwnPropertyDescriptor } = Reflect;
+    const NativeDate = Date;
+    const { UTC, parse } = NativeDate;
+    const prototype = NativeDate.prototype;
+    const { getTime, getTimezoneOffset, toLocaleString, toLocaleDateString, toLocaleTimeString } = prototype;
+
+    function replace(object, name, value) {
+        const descriptor = getOwnPropertyDescriptor(object, name);
+
+        descriptor.value = value;
+
+        defineProperty(object, name, descriptor);
+    }
+
+    // `Math.random` is sfc32, seeded with the two halves of the seed.
+    let a = seedLow >>> 0;
+    let b = seedHigh >>> 0;
+    let c = 0x9e3779b9;
+    let d = 1;
+
+    function next() {
+        const t = (((a + b) >>> 0) + d) >>> 0;
+
+        d = (d + 1) >>> 0;
+        a = b ^ (b >>> 9);
+        b = (c + (c << 3)) >>> 0;
+        c = (c << 21) | (c >>> 11);
+        c = (c + t) >>> 0;
+
+        return t;
+    }
+
+    for (let i = 0; i < 12; i++) {
+        next();
+    }
+
+    replace(Math, 'random', {
+        random() {
+            return ((next() >>> 5) * 67108864 + (next() >>> 6)) / 9007199254740992;
+        },
+    }.random);
+
+    // Local time is UTC. Strings without a time zone are parsed as local time, so they're shifted by
+    // however far the process's time zone is from UTC.
+    function parseUtc(string) {
+        const time = parse(string);
+
+        if (time !== time || /^\d{4}(-\d\d(-\d\d)?)?$|(Z|[+-]\d\d:?\d\d|GMT|UTC)(\s*\(.*\))?$/i.test(string)) {
+            return time;
+        }
+
+        return time - apply(getTimezoneOffset, new NativeDate(time), []) * 60000;
+    }
+
+    const days = ['Sun', 'Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat'];
+    const months = ['Jan', 'Feb', 'Mar', 'Apr', 'May', 'Jun', 'Jul', 'Aug', 'Sep', 'Oct', 'Nov', 'Dec'];
+
+    function pad(value, length) {
+        const sign = value < 0 ? '-' : '';
+
+        return sign + `${value < 0 ? -value : value}`.padStart(length, '0');
+    }
+
+    const methods = {
+        getTimezoneOffset() {
+            const time = apply(getTime, this, []);
+
+            return time === time ? 0 : NaN;
+        },
+        toString() {
+            const time = apply(getTime, this, []);
+
+            return time === time ? `${apply(methods.toDateString, this, [])} ${apply(methods.toTimeString, this, [])}` : 'Invalid Date';
+        },
+        toDateString() {
+            const time = apply(getTime, this, []);
+
+            if (time !== time) {
+                return 'Invalid Date';
+            }
+
+            const date = new NativeDate(time);
+
+            return `${days[date.getUTCDay()]} ${months[date.getUTCMonth()]} ${pad(date.getUTCDate(), 2)} ${pad(date.getUTCFullYear(), 4)}`;
+        },
+        toTimeString() {
+            const time = apply(getTime, this, []);
+
+            if (time !== time) {
+                return 'Invalid Date';
+            }
+
+            const date = new NativeDate(time);
+
+            return `${pad(date.getUTCHours(), 2)}:${pad(date.getUTCMinutes(), 2)}:${pad(date.getUTCSeconds(), 2)} GMT+0000 (Coordinated Universal Time)`;
+        },
+        toLocaleString(locales, options) {
+            return apply(toLocaleString, this, [locales === undefined ? locale : locales, inUtc(options)]);
+        },
+        toLocaleDateString(locales, options) {
+            return apply(toLocaleDateString, this, [locales === undefined ? locale : locales, inUtc(options)]);
+        },
+        toLocaleTimeString(locales, options) {
+            return apply(toLocaleTimeString, this, [locales === undefined ? locale : locales, inUtc(options)]);
+        },
+    };
+
+    for (const name of ['FullYear', 'Month', 'Date', 'Day', 'Hours', 'Minutes', 'Seconds', 'Milliseconds']) {
+        replace(prototype, `get${name}`, prototype[`getUTC${name}`]);
+
+        if (name !== 'Day') {
+            replace(prototype, `set${name}`, prototype[`setUTC${name}`]);
+        }
+    }
+
+    for (const name of Object.keys(methods)) {
+        replace(prototype, name, methods[name]);
+    }
+
+    function inUtc(options) {
+        if (options === undefined) {
+            return { timeZone: 'UTC' };
+        }
+
+        const copy = { ...Object(options) };
+
+        if (copy.timeZone === undefined) {
+            copy.timeZone = 'UTC';
+        }
+
+        return copy;
+    }
+
+    const DeterministicDate = new Proxy(NativeDate, {
+        construct(target, args, newTarget) {
+            if (args.length === 0) {
+                return construct(target, [now()], newTarget);
+            }
+
+            if (args.length === 1) {
+                return construct(target, [typeof args[0] === 'string' ? parseUtc(args[0]) : args[0]], newTarget);
+            }
+
+            return construct(target, [apply(UTC, undefined, args)], newTarget);
+        },
+        apply() {
+            return apply(methods.toString, new NativeDate(now()), []);
+        },
+    });
+
+    replace(NativeDate, 'now', { now() { return now(); } }.now);
+    replace(NativeDate, 'parse', { parse(string) { return parseUtc(`${string}`); } }.parse);
+    replace(prototype, 'constructor', DeterministicDate);
+    replace(globalThis, 'Date', DeterministicDate);
+
+    // `Intl` defaults to the locale, and date formats to UTC.
+    for (const name of ['Collator', 'DateTimeFormat', 'DisplayNames', 'ListFormat', 'NumberFormat', 'PluralRules', 'RelativeTimeFormat', 'Segmenter']) {
+        if (typeof Intl[name] !== 'function') {
+            continue;
+        }
+
+        const defaults = (args) => {
+            const copy = [...args];
+
+            if (copy[0] === undefined) {
+                copy[0] = locale;
+            }
+
+            if (name === 'DateTimeFormat') {
+                copy[1] = inUtc(copy[1]);
+            }
+
+            return copy;
+        };
+
+        replace(Intl, name, new Proxy(Intl[name], {
+            construct(target, args, newTarget) {
+                return construct(target, defaults(args), newTarget);
+            },
+            apply(target, thisArgument, args) {
+                return apply(target, thisArgument, defaults(args));
+            },
+        }));
+    }
+
+    const localized = [
+        [Number.prototype, 'toLocaleString', 0],
+        [BigInt.prototype, 'toLocaleString', 0],
+        [String.prototype, 'localeCompare', 1],
+        [String.prototype, 'toLocaleLowerCase', 0],
+        [String.prototype, 'toLocaleUpperCase', 0],
+    ];
+
+    for (const [object, name, index] of localized) {
+        const method = object[name];
+
+        replace(object, name, {
+            [name](...args) {
+                if (args[index] === undefined) {
+                    args[index] = locale;
+                }
+
+                return apply(method, this, args);
+            },
+        }[name]);
+    }
+})
+"#;
+
+pub fn install(scope: &mut v8::HandleScope, options: &DeterministicOptions) -> Option<()> {
+    scope.set_slot(Clock { time: options.time });
+
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let source = v8::String::new(scope, MAKE_DETERMINISTIC)?;
+    let script = v8::Script::compile(scope, source, None)?;
+    let make_deterministic = v8::Local::<v8::Function>::try_from(script.run(scope)?).ok()?;
+
+    let now = v8::Function::new(scope, now)?;
+    let seed_low = v8::Number::new(scope, (options.random_seed & 0xffff_ffff) as f64);
+    let seed_high = v8::Number::new(scope, (options.random_seed >> 32) as f64);
+    let locale = v8::String::new(scope, &options.locale)?;
+
+    let receiver = v8::undefined(scope);
+
+    make_deterministic.call(
+        scope,
+        receiver.into(),
+        &[now.into(), seed_low.into(), seed_high.into(), locale.into()],
+    )?;
+
+    Some(())
+}
+
+// Does nothing unless the context was made deterministic.
+pub fn set_time(isolate: &mut v8::Isolate, time: f64) {
+    if let Some(clock) = isolate.get_slot_mut::<Clock>() {
+        clock.time = time;
+    }
+}
+
+// The host's clock, `None` unless the context was made deterministic.
+pub fn time(isolate: &v8::Isolate) -> Option<f64> {
+    isolate.get_slot::<Clock>().map(|clock| clock.time)
+}
+
+fn now(scope: &mut v8::HandleScope, _args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    let time = time(scope).unwrap_or(0.0);
+
+    rv.set(v8::Number::new(scope, time).into());
+}
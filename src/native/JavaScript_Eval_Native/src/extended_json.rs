@@ -0,0 +1,374 @@
+use std::convert::TryFrom;
+
+use crate::v8facade::{exception_message, new_string};
+
+// Plain JSON can't carry these, so they're written as single key objects:
+//
+//   {"$bigint": "123"}         BigInts of any size, as decimal text.
+//   {"$number": "NaN"}         NaN, "Infinity", "-Infinity" and "-0".
+//   {"$date": 86400000}        Milliseconds since the epoch, null for an invalid date.
+//   {"$undefined": true}       Kept in objects and arrays instead of being dropped or turned into null.
+//
+// Everything else follows JSON.stringify, except that `toJSON` isn't consulted. An object that has
+// exactly one of these keys and nothing else is always read back as the tagged value.
+const BIGINT: &str = "$bigint";
+const NUMBER: &str = "$number";
+const DATE: &str = "$date";
+const UNDEFINED: &str = "$undefined";
+
+// Deep enough for any reasonable value, shallow enough that the native stack survives.
+const MAX_DEPTH: usize = 512;
+
+pub fn stringify(
+    scope: &mut v8::HandleScope,
+    value: v8::Local<v8::Value>,
+) -> Result<String, String> {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let mut path = Vec::new();
+    let tagged = encode(tc, value, &mut path)?;
+
+    match v8::json::stringify(tc, tagged) {
+        Some(json) => Ok(json.to_rust_string_lossy(tc)),
+        None => Err(exception_message(tc)),
+    }
+}
+
+pub fn parse<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    json: v8::Local<v8::String>,
+) -> Result<v8::Local<'s, v8::Value>, String> {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let value = match v8::json::parse(tc, json) {
+        Some(value) => value,
+        None => return Err(exception_message(tc)),
+    };
+
+    decode(tc, value, 0)
+}
+
+fn new_key<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    key: &str,
+) -> Result<v8::Local<'s, v8::Name>, String> {
+    new_string(scope, key).map(|key| key.into())
+}
+
+fn tag<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    value: v8::Local<v8::Value>,
+) -> Result<v8::Local<'s, v8::Value>, String> {
+    let tagged = v8::Object::new(scope);
+    let name = new_key(scope, name)?;
+
+    tagged.create_data_property(scope, name, value);
+
+    Ok(tagged.into())
+}
+
+fn own_keys<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<v8::Object>,
+) -> Option<v8::Local<'s, v8::Array>> {
+    object.get_own_property_names(
+        scope,
+        v8::GetPropertyNamesArgs {
+            key_conversion: v8::KeyConversionMode::ConvertToString,
+            ..Default::default()
+        },
+    )
+}
+
+fn encode<'s>(
+    tc: &mut v8::TryCatch<'s, v8::HandleScope>,
+    value: v8::Local<'s, v8::Value>,
+    path: &mut Vec<v8::Local<'s, v8::Object>>,
+) -> Result<v8::Local<'s, v8::Value>, String> {
+    if value.is_undefined() {
+        let marker = v8::Boolean::new(tc, true).into();
+
+        return tag(tc, UNDEFINED, marker);
+    }
+
+    if let Ok(bigint) = v8::Local::<v8::BigInt>::try_from(value) {
+        let digits = bigint.to_rust_string_lossy(tc);
+        let digits = new_string(tc, &digits)?.into();
+
+        return tag(tc, BIGINT, digits);
+    }
+
+    if let Ok(number) = v8::Local::<v8::Number>::try_from(value) {
+        let n = number.value();
+
+        let special = if n.is_nan() {
+            "NaN"
+        } else if n == f64::INFINITY {
+            "Infinity"
+        } else if n == f64::NEG_INFINITY {
+            "-Infinity"
+        } else if n == 0.0 && n.is_sign_negative() {
+            "-0"
+        } else {
+            return Ok(value);
+        };
+
+        let special = new_string(tc, special)?.into();
+
+        return tag(tc, NUMBER, special);
+    }
+
+    if let Ok(date) = v8::Local::<v8::Date>::try_from(value) {
+        let ms = date.value_of();
+
+        let ms = if ms.is_nan() {
+            v8::null(tc).into()
+        } else {
+            v8::Number::new(tc, ms).into()
+        };
+
+        return tag(tc, DATE, ms);
+    }
+
+    let object = match v8::Local::<v8::Object>::try_from(value) {
+        Ok(object) if !value.is_function() => object,
+        _ => return Ok(value),
+    };
+
+    if path.iter().any(|o| o.strict_equals(value)) {
+        return Err(String::from(
+            "TypeError: Converting circular structure to extended JSON",
+        ));
+    }
+
+    if path.len() >= MAX_DEPTH {
+        return Err(format!(
+            "RangeError: Values nested more than {} levels deep can't be converted to extended JSON",
+            MAX_DEPTH
+        ));
+    }
+
+    path.push(object);
+
+    let result = if let Ok(array) = v8::Local::<v8::Array>::try_from(value) {
+        let mut elements = Vec::with_capacity(array.length() as usize);
+
+        for i in 0..array.length() {
+            let element = match array.get_index(tc, i) {
+                Some(element) => element,
+                None => return Err(exception_message(tc)),
+            };
+
+            // Functions and symbols become null in arrays, the same as JSON.stringify.
+            let element = if element.is_function() || element.is_symbol() {
+                v8::null(tc).into()
+            } else {
+                encode(tc, element, path)?
+            };
+
+            elements.push(element);
+        }
+
+        v8::Array::new_with_elements(tc, &elements).into()
+    } else {
+        let keys = match own_keys(tc, object) {
+            Some(keys) => keys,
+            None => return Err(exception_message(tc)),
+        };
+
+        let copy = v8::Object::new(tc);
+
+        for i in 0..keys.length() {
+            let key = match keys.get_index(tc, i) {
+                Some(key) => key,
+                None => return Err(exception_message(tc)),
+            };
+
+            let property = match object.get(tc, key) {
+                Some(property) => property,
+                None => return Err(exception_message(tc)),
+            };
+
+            // ...and are left out of objects.
+            if property.is_function() || property.is_symbol() {
+                continue;
+            }
+
+            let property = encode(tc, property, path)?;
+
+            if let Ok(key) = v8::Local::<v8::Name>::try_from(key) {
+                copy.create_data_property(tc, key, property);
+            }
+        }
+
+        copy.into()
+    };
+
+    path.pop();
+
+    Ok(result)
+}
+
+fn decode<'s>(
+    tc: &mut v8::TryCatch<v8::HandleScope<'s>>,
+    value: v8::Local<'s, v8::Value>,
+    depth: usize,
+) -> Result<v8::Local<'s, v8::Value>, String> {
+    // JSON.parse only ever produces plain objects and arrays, but reads are checked like in `encode`
+    // all the same.
+    let object = match v8::Local::<v8::Object>::try_from(value) {
+        Ok(object) => object,
+        Err(_) => return Ok(value),
+    };
+
+    if depth >= MAX_DEPTH {
+        return Err(format!(
+            "Values nested more than {} levels deep can't be read from extended JSON.",
+            MAX_DEPTH
+        ));
+    }
+
+    if let Ok(array) = v8::Local::<v8::Array>::try_from(value) {
+        for i in 0..array.length() {
+            let element = match array.get_index(tc, i) {
+                Some(element) => element,
+                None => return Err(exception_message(tc)),
+            };
+
+            let element = decode(tc, element, depth + 1)?;
+
+            let key = new_key(tc, &i.to_string())?;
+            array.create_data_property(tc, key, element);
+        }
+
+        return Ok(value);
+    }
+
+    let keys = match own_keys(tc, object) {
+        Some(keys) => keys,
+        None => return Err(exception_message(tc)),
+    };
+
+    if keys.length() == 1 {
+        let key = match keys.get_index(tc, 0) {
+            Some(key) => key,
+            None => return Err(exception_message(tc)),
+        };
+
+        let name = key.to_rust_string_lossy(tc);
+
+        let payload = match object.get(tc, key) {
+            Some(payload) => payload,
+            None => return Err(exception_message(tc)),
+        };
+
+        if let Some(tagged) = decode_tag(tc, &name, payload)? {
+            return Ok(tagged);
+        }
+    }
+
+    for i in 0..keys.length() {
+        let key = match keys.get_index(tc, i) {
+            Some(key) => key,
+            None => return Err(exception_message(tc)),
+        };
+
+        let property = match object.get(tc, key) {
+            Some(property) => property,
+            None => return Err(exception_message(tc)),
+        };
+
+        let property = decode(tc, property, depth + 1)?;
+
+        if let Ok(key) = v8::Local::<v8::Name>::try_from(key) {
+            object.create_data_property(tc, key, property);
+        }
+    }
+
+    Ok(value)
+}
+
+fn decode_tag<'s>(
+    tc: &mut v8::TryCatch<v8::HandleScope<'s>>,
+    name: &str,
+    payload: v8::Local<'s, v8::Value>,
+) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
+    let invalid = |payload: String| format!("`{}` can't be read from `{}`.", name, payload);
+
+    let value = match name {
+        BIGINT if payload.is_string() => {
+            let digits = payload.to_rust_string_lossy(tc);
+
+            let (negative, words) = parse_bigint(&digits).ok_or_else(|| invalid(digits))?;
+
+            v8::BigInt::new_from_words(tc, negative, &words)
+                .ok_or_else(|| String::from("The `$bigint` is larger than V8 allows."))?
+                .into()
+        }
+
+        NUMBER if payload.is_string() => {
+            let n = match payload.to_rust_string_lossy(tc).as_str() {
+                "NaN" => f64::NAN,
+                "Infinity" => f64::INFINITY,
+                "-Infinity" => f64::NEG_INFINITY,
+                "-0" => -0.0,
+                other => return Err(invalid(String::from(other))),
+            };
+
+            v8::Number::new(tc, n).into()
+        }
+
+        DATE if payload.is_number() || payload.is_null() => {
+            let ms = match v8::Local::<v8::Number>::try_from(payload) {
+                Ok(ms) => ms.value(),
+                Err(_) => f64::NAN,
+            };
+
+            v8::Date::new(tc, ms)
+                .ok_or_else(|| invalid(ms.to_string()))?
+                .into()
+        }
+
+        UNDEFINED if payload.is_true() => v8::undefined(tc).into(),
+
+        BIGINT | NUMBER | DATE | UNDEFINED => {
+            return Err(invalid(payload.to_rust_string_lossy(tc)));
+        }
+
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
+// Decimal text to the sign and little endian 64-bit words `v8::BigInt::new_from_words` expects.
+fn parse_bigint(digits: &str) -> Option<(bool, Vec<u64>)> {
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, digits),
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut words: Vec<u64> = Vec::new();
+
+    for digit in digits.bytes() {
+        let mut carry = (digit - b'0') as u128;
+
+        for word in words.iter_mut() {
+            let product = *word as u128 * 10 + carry;
+
+            *word = product as u64;
+            carry = product >> 64;
+        }
+
+        if carry > 0 {
+            words.push(carry as u64);
+        }
+    }
+
+    Some((negative, words))
+}
@@ -1,16 +1,28 @@
 use std::ffi::CStr;
 
-use crate::Primitive;
+use crate::{
+    primitive_result::ByteBuffer,
+    tagged_primitive::{PrimitiveKind, TaggedPrimitive, TAGGED_PRIMITIVE_VERSION},
+    BinaryPrimitive, Primitive,
+};
 
 
 #[derive(Debug)]
 pub enum FunctionParameter {
+    NullValue,
+    UndefinedValue,
     StringValue(String),
+    Utf16StringValue(Vec<u16>),
     SymbolValue(String),
     NumberValue(f64),
     BigIntValue(i64),
     BoolValue(bool),
     ObjectValue(String),
+
+    // Surfaces in JavaScript as an `ArrayBuffer` that takes ownership of the bytes.
+    ArrayBufferValue(Vec<u8>),
+    // Surfaces in JavaScript as a `Uint8Array` over a new `ArrayBuffer` that takes ownership of the bytes.
+    Uint8ArrayValue(Vec<u8>),
 }
 
 impl FunctionParameter {
@@ -59,4 +71,95 @@ impl FunctionParameter {
 
         unreachable!();
     }
-}
\ No newline at end of file
+
+    pub fn from_binary(p: &BinaryPrimitive) -> FunctionParameter {
+        let bytes = if p.data.is_null() || p.length == 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(p.data, p.length).to_vec() }
+        };
+
+        if p.as_array_buffer {
+            FunctionParameter::ArrayBufferValue(bytes)
+        } else {
+            FunctionParameter::Uint8ArrayValue(bytes)
+        }
+    }
+
+    pub fn from_tagged(p: &TaggedPrimitive) -> Result<FunctionParameter, String> {
+        if p.version != TAGGED_PRIMITIVE_VERSION {
+            return Err(format!(
+                "Unsupported tagged primitive version {}, expected {}.",
+                p.version, TAGGED_PRIMITIVE_VERSION
+            ));
+        }
+
+        let kind = PrimitiveKind::from_u32(p.kind)
+            .ok_or_else(|| format!("Unknown primitive kind {}.", p.kind))?;
+
+        let parameter = unsafe {
+            match kind {
+                PrimitiveKind::Null => FunctionParameter::NullValue,
+                PrimitiveKind::Undefined => FunctionParameter::UndefinedValue,
+                PrimitiveKind::String => FunctionParameter::StringValue(buffer_to_string(p)),
+                PrimitiveKind::Utf16String => {
+                    FunctionParameter::Utf16StringValue(buffer_to_utf16(p)?)
+                }
+                PrimitiveKind::Symbol => FunctionParameter::SymbolValue(buffer_to_string(p)),
+                PrimitiveKind::Number => FunctionParameter::NumberValue(p.value.number),
+                PrimitiveKind::BigInt => FunctionParameter::BigIntValue(p.value.bigint),
+                PrimitiveKind::Bool => FunctionParameter::BoolValue(p.value.boolean != 0),
+                PrimitiveKind::Array | PrimitiveKind::Object => {
+                    FunctionParameter::ObjectValue(buffer_to_string(p))
+                }
+                PrimitiveKind::Binary => {
+                    FunctionParameter::Uint8ArrayValue(buffer_to_vec(p.value.buffer))
+                }
+
+                PrimitiveKind::Handle => {
+                    return Err(String::from(
+                        "Handle parameters aren't supported by this version of the library.",
+                    ))
+                }
+
+                PrimitiveKind::Error
+                | PrimitiveKind::Serialized
+                | PrimitiveKind::Function
+                | PrimitiveKind::Date
+                | PrimitiveKind::RegExp => {
+                    return Err(format!("{:?} can't be passed as a parameter.", kind))
+                }
+            }
+        };
+
+        Ok(parameter)
+    }
+}
+
+unsafe fn buffer_to_vec(buffer: ByteBuffer) -> Vec<u8> {
+    if buffer.data.is_null() || buffer.length == 0 {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts(buffer.data, buffer.length).to_vec()
+}
+
+unsafe fn buffer_to_string(p: &TaggedPrimitive) -> String {
+    String::from_utf8_lossy(&buffer_to_vec(p.value.buffer)).into_owned()
+}
+
+unsafe fn buffer_to_utf16(p: &TaggedPrimitive) -> Result<Vec<u16>, String> {
+    let bytes = buffer_to_vec(p.value.buffer);
+
+    if bytes.len() % 2 != 0 {
+        return Err(format!(
+            "A UTF-16 string can't be {} bytes long, its length is in bytes and must be even.",
+            bytes.len()
+        ));
+    }
+
+    Ok(bytes
+        .chunks(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect())
+}
@@ -0,0 +1,118 @@
+use crate::{
+    intrinsics,
+    modules::throw_error,
+    v8facade::{exception_message, HostGlobal},
+};
+
+// The names of the global object's own string keyed properties, enumerable or not.
+pub fn names<'s>(scope: &mut v8::HandleScope<'s>) -> Option<v8::Local<'s, v8::Array>> {
+    let global = scope.get_current_context().global(scope);
+
+    global.get_own_property_names(
+        scope,
+        v8::GetPropertyNamesArgs {
+            mode: v8::KeyCollectionMode::OwnOnly,
+            property_filter: v8::SKIP_SYMBOLS,
+            index_filter: v8::IndexFilter::SkipIndices,
+            key_conversion: v8::KeyConversionMode::ConvertToString,
+        },
+    )
+}
+
+// Deletes every global that isn't in `allowed`. The few V8 makes non-configurable, like `undefined`,
+// stay regardless.
+pub fn retain(scope: &mut v8::HandleScope, allowed: &[String]) -> Option<()> {
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let global = scope.get_current_context().global(scope);
+    let names = names(scope)?;
+
+    for i in 0..names.length() {
+        let name = names.get_index(scope, i)?;
+
+        if !allowed.contains(&name.to_rust_string_lossy(scope)) {
+            global.delete(scope, name)?;
+        }
+    }
+
+    Some(())
+}
+
+// A global whose JSON doesn't parse throws why when it's read, there's nothing to report it to while
+// the context is being created.
+pub fn define(scope: &mut v8::HandleScope, globals: &[HostGlobal]) -> Option<()> {
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let global = scope.get_current_context().global(scope);
+
+    for HostGlobal { name, json } in globals {
+        let key = v8::String::new(scope, name)?;
+
+        let tc = &mut v8::TryCatch::new(scope);
+
+        let value = v8::String::new(tc, json).and_then(|json| v8::json::parse(tc, json));
+
+        match value {
+            Some(value) => {
+                global.set(tc, key.into(), value)?;
+            }
+
+            None => {
+                let message = format!(
+                    "The host global `{}` isn't valid JSON: {}",
+                    name,
+                    exception_message(tc)
+                );
+
+                tc.reset();
+
+                define_throwing(tc, global, key, &message)?;
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn define_throwing(
+    scope: &mut v8::HandleScope,
+    global: v8::Local<v8::Object>,
+    key: v8::Local<v8::String>,
+    message: &str,
+) -> Option<()> {
+    let message = v8::String::new(scope, message)?;
+
+    let get = v8::Function::builder(throw_message)
+        .data(message.into())
+        .build(scope)?;
+
+    let descriptor = v8::Object::new(scope);
+
+    let get_key = v8::String::new(scope, "get")?;
+    descriptor.set(scope, get_key.into(), get.into())?;
+
+    let configurable_key = v8::String::new(scope, "configurable")?;
+    let configurable = v8::Boolean::new(scope, true);
+    descriptor.set(scope, configurable_key.into(), configurable.into())?;
+
+    let define_property = intrinsics::get_function(scope, "defineProperty")?;
+    let receiver = v8::undefined(scope);
+
+    define_property.call(
+        scope,
+        receiver.into(),
+        &[global.into(), key.into(), descriptor.into()],
+    )?;
+
+    Some(())
+}
+
+fn throw_message(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _rv: v8::ReturnValue,
+) {
+    let message = args.data().to_rust_string_lossy(scope);
+
+    throw_error(scope, &message);
+}
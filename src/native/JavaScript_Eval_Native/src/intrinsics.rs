@@ -0,0 +1,195 @@
+use std::convert::TryFrom;
+
+// Built-ins the facade relies on are captured when the context is created, before any host script has
+// had a chance to replace them, and kept behind a private symbol on the global object so scripts can't
+// reach them.
+const INTRINSICS_KEY: &str = "JavaScript.Eval.intrinsics";
+
+const CAPTURE_INTRINSICS: &str = r#"
+(function () {
+    'use strict';
+
+    const intrinsics = Object.create(null);
+
+    intrinsics.functionToString = Function.prototype.toString;
+    intrinsics.regExpSource = Object.getOwnPropertyDescriptor(RegExp.prototype, 'source').get;
+    intrinsics.regExpFlags = Object.getOwnPropertyDescriptor(RegExp.prototype, 'flags').get;
+    intrinsics.defineProperty = Reflect.defineProperty;
+
+    // Missing when Wasm is disabled.
+    if (typeof WebAssembly === 'object') {
+        intrinsics.wasmModule = WebAssembly.Module;
+        intrinsics.wasmModuleExports = WebAssembly.Module.exports;
+        intrinsics.wasmInstance = WebAssembly.Instance;
+        intrinsics.wasmInstanceExports = Object.getOwnPropertyDescriptor(WebAssembly.Instance.prototype, 'exports').get;
+    }
+
+    intrinsics.lockdown = lockdown();
+
+    return intrinsics;
+
+    // Freezes every built-in and makes the globals that hold them read-only. Everything it uses is
+    // captured now, so preludes that run before it can't get in its way either.
+    function lockdown() {
+        const { freeze, getPrototypeOf } = Object;
+        const { apply, defineProperty, getOwnPropertyDescriptor, ownKeys } = Reflect;
+        const { add, has } = WeakSet.prototype;
+        const WeakSetConstructor = WeakSet;
+        const TypeErrorConstructor = TypeError;
+        const StringConstructor = String;
+
+        const global = globalThis;
+        const names = ownKeys(global);
+
+        // Built-ins that no global leads to.
+        const hidden = [
+            getPrototypeOf(function* () {}),
+            getPrototypeOf(async function () {}),
+            getPrototypeOf(async function* () {}),
+            getPrototypeOf(Int8Array),
+            getPrototypeOf([][Symbol.iterator]()),
+            getPrototypeOf(new Map()[Symbol.iterator]()),
+            getPrototypeOf(new Set()[Symbol.iterator]()),
+            getPrototypeOf(''[Symbol.iterator]()),
+            getPrototypeOf(''.matchAll(/./g)),
+        ];
+
+        // Assigning a property that a frozen prototype already has would fail for every object that
+        // inherits it, so these become accessors that define the property on the object instead.
+        const overridable = [
+            [Object.prototype, ['constructor', 'toString', 'valueOf', 'toLocaleString']],
+            [Error.prototype, ['constructor', 'name', 'message', 'toString']],
+            [Function.prototype, ['constructor', 'toString']],
+            [Promise.prototype, ['constructor']],
+        ];
+
+        function enableOverride(object, name) {
+            const descriptor = getOwnPropertyDescriptor(object, name);
+
+            if (descriptor === undefined || !descriptor.writable) {
+                return;
+            }
+
+            const value = descriptor.value;
+
+            defineProperty(object, name, {
+                get() {
+                    return value;
+                },
+                set(value) {
+                    if (this === object) {
+                        throw new TypeErrorConstructor(`Cannot assign to read only property '${StringConstructor(name)}' of a built-in.`);
+                    }
+
+                    defineProperty(this, name, { value, writable: true, enumerable: true, configurable: true });
+                },
+                enumerable: descriptor.enumerable,
+                configurable: false,
+            });
+        }
+
+        return function () {
+            const frozen = new WeakSetConstructor();
+            const pending = [];
+            let count = 0;
+
+            function visit(value) {
+                if (((typeof value === 'object' && value !== null) || typeof value === 'function') && value !== global) {
+                    pending[count++] = value;
+                }
+            }
+
+            for (let i = 0; i < overridable.length; i++) {
+                const properties = overridable[i][1];
+
+                for (let j = 0; j < properties.length; j++) {
+                    enableOverride(overridable[i][0], properties[j]);
+                }
+            }
+
+            for (let i = 0; i < hidden.length; i++) {
+                visit(hidden[i]);
+            }
+
+            for (let i = 0; i < names.length; i++) {
+                const descriptor = getOwnPropertyDescriptor(global, names[i]);
+
+                // Preludes may have removed it.
+                if (descriptor === undefined) {
+                    continue;
+                }
+
+                visit(descriptor.value);
+                visit(descriptor.get);
+                visit(descriptor.set);
+
+                if ('value' in descriptor) {
+                    descriptor.writable = false;
+                }
+
+                descriptor.configurable = false;
+
+                defineProperty(global, names[i], descriptor);
+            }
+
+            while (count > 0) {
+                const value = pending[--count];
+
+                if (apply(has, frozen, [value])) {
+                    continue;
+                }
+
+                apply(add, frozen, [value]);
+                freeze(value);
+
+                visit(getPrototypeOf(value));
+
+                const keys = ownKeys(value);
+
+                for (let i = 0; i < keys.length; i++) {
+                    const descriptor = getOwnPropertyDescriptor(value, keys[i]);
+
+                    visit(descriptor.value);
+                    visit(descriptor.get);
+                    visit(descriptor.set);
+                }
+            }
+        };
+    }
+})();
+"#;
+
+pub fn install(scope: &mut v8::HandleScope) -> Option<()> {
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let source = v8::String::new(scope, CAPTURE_INTRINSICS)?;
+    let script = v8::Script::compile(scope, source, None)?;
+    let intrinsics = script.run(scope)?;
+
+    let key = v8::String::new(scope, INTRINSICS_KEY)?;
+    let key = v8::Private::for_api(scope, Some(key));
+
+    let global = scope.get_current_context().global(scope);
+
+    global.set_private(scope, key, intrinsics)?;
+
+    Some(())
+}
+
+pub fn get_function<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+) -> Option<v8::Local<'s, v8::Function>> {
+    let key = v8::String::new(scope, INTRINSICS_KEY)?;
+    let key = v8::Private::for_api(scope, Some(key));
+
+    let global = scope.get_current_context().global(scope);
+
+    let intrinsics = global.get_private(scope, key)?;
+    let intrinsics = v8::Local::<v8::Object>::try_from(intrinsics).ok()?;
+
+    let name = v8::String::new(scope, name)?;
+    let function = intrinsics.get(scope, name.into())?;
+
+    v8::Local::<v8::Function>::try_from(function).ok()
+}
@@ -1,13 +1,36 @@
+// The `extern "C"` functions are only ever called from .NET, which can't read a `# Safety` section.
+#![allow(clippy::missing_safety_doc)]
+
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use function_parameter::FunctionParameter;
-use primitive_result::PrimitiveResult;
-use v8facade::{JavaScriptError, Output, V8Facade};
+use primitive_result::{PrimitiveResult, UnsafeJavaScriptError};
+use syntax_check_result::SyntaxCheckResult;
+use tagged_primitive::TaggedPrimitive;
+use v8facade::{
+    DeterministicOptions, HostGlobal, JavaScriptError, MicrotaskPolicy, Output, SourceKind,
+    V8Facade, V8FacadeOptions, ValueEncoding, WasmImport,
+};
 
+mod commonjs;
+mod deterministic;
+mod extended_json;
 pub mod function_parameter;
+mod globals;
+mod intrinsics;
+mod modules;
 pub mod primitive_result;
+mod rejections;
+pub mod structured_clone;
+pub mod syntax_check_result;
+pub mod tagged_primitive;
+mod timers;
+mod top_level_await;
 pub mod v8facade;
+mod wasm;
 
 #[repr(C)]
 #[derive(Debug)]
@@ -26,6 +49,133 @@ pub struct Primitive {
     pub object_value: *mut c_char,
 }
 
+// A byte buffer argument. The host keeps ownership of `data`: the bytes are copied once into an
+// allocation that V8 takes over, so the host may release its buffer as soon as the call returns.
+#[repr(C)]
+#[derive(Debug)]
+pub struct BinaryPrimitive {
+    pub data: *const u8,
+    pub length: usize,
+
+    // Passed as an `ArrayBuffer` when set, otherwise as a `Uint8Array`.
+    pub as_array_buffer: bool,
+}
+
+// See `V8FacadeOptions`, null pointers leave an option unset.
+#[repr(C)]
+#[derive(Debug)]
+pub struct UnsafeV8FacadeOptions {
+    pub commonjs_root: *const c_char,
+    pub disable_wasm: bool,
+    pub disallow_code_generation: bool,
+
+    pub allowed_globals: *const *const c_char,
+    pub allowed_global_count: usize,
+
+    pub host_globals: *const UnsafeHostGlobal,
+    pub host_global_count: usize,
+
+    // The rest are only read when `deterministic` is set, a null `locale` is `en-US`.
+    pub deterministic: bool,
+    pub random_seed: u64,
+    pub time: f64,
+    pub locale: *const c_char,
+}
+
+impl UnsafeV8FacadeOptions {
+    unsafe fn to_options(&self) -> V8FacadeOptions {
+        let commonjs_root = if self.commonjs_root.is_null() {
+            None
+        } else {
+            let root = CStr::from_ptr(self.commonjs_root).to_string_lossy();
+
+            Some(PathBuf::from(root.into_owned()))
+        };
+
+        let allowed_globals = if self.allowed_globals.is_null() {
+            None
+        } else {
+            let names = std::slice::from_raw_parts(self.allowed_globals, self.allowed_global_count);
+
+            Some(
+                names
+                    .iter()
+                    .map(|name| CStr::from_ptr(*name).to_string_lossy().into_owned())
+                    .collect(),
+            )
+        };
+
+        let host_globals = if self.host_globals.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(self.host_globals, self.host_global_count)
+                .iter()
+                .map(|g| g.to_host_global())
+                .collect()
+        };
+
+        let deterministic = if self.deterministic {
+            let mut deterministic = DeterministicOptions {
+                random_seed: self.random_seed,
+                time: self.time,
+                ..Default::default()
+            };
+
+            if !self.locale.is_null() {
+                deterministic.locale = CStr::from_ptr(self.locale).to_string_lossy().into_owned();
+            }
+
+            Some(deterministic)
+        } else {
+            None
+        };
+
+        V8FacadeOptions {
+            commonjs_root,
+            disable_wasm: self.disable_wasm,
+            disallow_code_generation: self.disallow_code_generation,
+            allowed_globals,
+            host_globals,
+            deterministic,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct UnsafeHostGlobal {
+    pub name: *const c_char,
+    pub json: *const c_char,
+}
+
+impl UnsafeHostGlobal {
+    unsafe fn to_host_global(&self) -> HostGlobal {
+        HostGlobal {
+            name: CStr::from_ptr(self.name).to_string_lossy().into_owned(),
+            json: CStr::from_ptr(self.json).to_string_lossy().into_owned(),
+        }
+    }
+}
+
+// See `WasmImport`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct UnsafeWasmImport {
+    pub module: *const c_char,
+    pub name: *const c_char,
+    pub global: *const c_char,
+}
+
+impl UnsafeWasmImport {
+    unsafe fn to_import(&self) -> WasmImport {
+        WasmImport {
+            module: CStr::from_ptr(self.module).to_string_lossy().into_owned(),
+            name: CStr::from_ptr(self.name).to_string_lossy().into_owned(),
+            global: CStr::from_ptr(self.global).to_string_lossy().into_owned(),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct V8HeapStatistics {
@@ -44,6 +194,54 @@ pub struct V8HeapStatistics {
     pub total_global_handles_size: usize,
 }
 
+unsafe fn utf8_from_raw_parts(data: *const u8, length: usize) -> String {
+    if data.is_null() || length == 0 {
+        return String::new();
+    }
+
+    String::from_utf8_lossy(std::slice::from_raw_parts(data, length)).into_owned()
+}
+
+// Strings in tagged parameters carry their length, so they can hold NULs and, as UTF-16, unpaired
+// surrogates.
+unsafe fn tagged_parameters(
+    parameters: *const TaggedPrimitive,
+    parameter_count: usize,
+) -> Result<Vec<FunctionParameter>, String> {
+    if parameter_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    std::slice::from_raw_parts(parameters, parameter_count)
+        .iter()
+        .map(FunctionParameter::from_tagged)
+        .collect()
+}
+
+unsafe fn utf16_from_raw_parts(data: *const u16, length: usize) -> Vec<u16> {
+    if data.is_null() || length == 0 {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts(data, length).to_vec()
+}
+
+// Returns null once V8 is initialized, otherwise why it couldn't be, to be freed with `free_string`.
+// `flags` may be null.
+#[no_mangle]
+pub unsafe extern "C" fn initialize_v8(flags: *const c_char, thread_pool_size: u32) -> *mut c_char {
+    let flags = if flags.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(flags).to_string_lossy().into_owned()
+    };
+
+    match V8Facade::initialize(&flags, thread_pool_size) {
+        Ok(()) => std::ptr::null_mut(),
+        Err(e) => primitive_result::into_c_string(e).into_raw(),
+    }
+}
+
 // http://jakegoulding.com/rust-ffi-omnibus/objects/
 #[no_mangle]
 pub extern "C" fn get_v8() -> *mut V8Facade {
@@ -51,18 +249,28 @@ pub extern "C" fn get_v8() -> *mut V8Facade {
 }
 
 #[no_mangle]
-pub extern "C" fn free_v8(v8_facade_ptr: *mut V8Facade) {
+pub unsafe extern "C" fn get_v8_with_options(
+    options: *const UnsafeV8FacadeOptions,
+) -> *mut V8Facade {
+    let options = {
+        assert!(!options.is_null());
+        (*options).to_options()
+    };
+
+    Box::into_raw(Box::new(V8Facade::with_options(options)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn free_v8(v8_facade_ptr: *mut V8Facade) {
     if v8_facade_ptr.is_null() {
         return;
     }
 
-    unsafe {
-        let v8_facade = Box::from_raw(v8_facade_ptr);
+    let v8_facade = Box::from_raw(v8_facade_ptr);
 
-        let _ = v8_facade.shutdown().unwrap();
+    v8_facade.shutdown().unwrap();
 
-        let _ = v8_facade.handle.join().unwrap();
-    }
+    let _ = v8_facade.handle.join().unwrap();
 }
 
 #[no_mangle]
@@ -82,6 +290,125 @@ pub unsafe extern "C" fn exec(
     PrimitiveResult::from_output(result).into_raw()
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn exec_with_await(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const c_char,
+    timeout_milliseconds: u64,
+) -> *mut PrimitiveResult {
+    let script = CStr::from_ptr(script).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance
+        .run_with_await(script, Duration::from_millis(timeout_milliseconds))
+        .unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exec_until_idle(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const c_char,
+    timeout_milliseconds: u64,
+) -> *mut PrimitiveResult {
+    let script = CStr::from_ptr(script).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance
+        .run_until_idle(script, Duration::from_millis(timeout_milliseconds))
+        .unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+// `name` is what the module's relative imports resolve against.
+#[no_mangle]
+pub unsafe extern "C" fn exec_module(
+    v8_facade_ptr: *mut V8Facade,
+    name: *const c_char,
+    script: *const c_char,
+    timeout_milliseconds: u64,
+) -> *mut PrimitiveResult {
+    let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+    let script = CStr::from_ptr(script).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance
+        .run_module(name, script, Duration::from_millis(timeout_milliseconds))
+        .unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exec_encoded(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const c_char,
+    encoding: ValueEncoding,
+) -> *mut PrimitiveResult {
+    let script = CStr::from_ptr(script).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.run_encoded(script, encoding).unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+// Pointer and length variants of `exec`. The script may contain NULs, and with the UTF-16 variant string results
+// come back in `utf16_string_value`.
+#[no_mangle]
+pub unsafe extern "C" fn exec_utf8(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const u8,
+    script_length: usize,
+) -> *mut PrimitiveResult {
+    let script = utf8_from_raw_parts(script, script_length);
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.run(script).unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exec_utf16(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const u16,
+    script_length: usize,
+) -> *mut PrimitiveResult {
+    let script = utf16_from_raw_parts(script, script_length);
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.run_utf16(script, ValueEncoding::Json).unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn begin_exec(
     v8_facade_ptr: *mut V8Facade,
@@ -116,7 +443,75 @@ pub unsafe extern "C" fn call(
     let parameters: &[Primitive] = std::slice::from_raw_parts(parameters, parameter_count);
     let parameters = parameters
         .iter()
-        .map(|p| FunctionParameter::from(p))
+        .map(FunctionParameter::from)
+        .collect();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match instance.call(func_name, parameters) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn call_encoded(
+    v8_facade_ptr: *mut V8Facade,
+    func_name: *const c_char,
+    parameters: *const Primitive,
+    parameter_count: usize,
+    encoding: ValueEncoding,
+) -> *mut PrimitiveResult {
+    let func_name = CStr::from_ptr(func_name).to_string_lossy().into_owned();
+
+    let parameters: &[Primitive] = std::slice::from_raw_parts(parameters, parameter_count);
+    let parameters = parameters
+        .iter()
+        .map(FunctionParameter::from)
+        .collect();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match instance.call_encoded(func_name, parameters, encoding) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+// Binary parameters are passed after the primitive parameters, in the order they're given.
+#[no_mangle]
+pub unsafe extern "C" fn call_binary(
+    v8_facade_ptr: *mut V8Facade,
+    func_name: *const c_char,
+    parameters: *const Primitive,
+    parameter_count: usize,
+    binary_parameters: *const BinaryPrimitive,
+    binary_parameter_count: usize,
+) -> *mut PrimitiveResult {
+    let func_name = CStr::from_ptr(func_name).to_string_lossy().into_owned();
+
+    let parameters: &[Primitive] = std::slice::from_raw_parts(parameters, parameter_count);
+    let binary_parameters: &[BinaryPrimitive] =
+        std::slice::from_raw_parts(binary_parameters, binary_parameter_count);
+
+    let parameters = parameters
+        .iter()
+        .map(FunctionParameter::from)
+        .chain(
+            binary_parameters
+                .iter()
+                .map(FunctionParameter::from_binary),
+        )
         .collect();
 
     let instance = {
@@ -126,10 +521,61 @@ pub unsafe extern "C" fn call(
 
     let result = match instance.call(func_name, parameters) {
         Ok(o) => o,
-        Err(e) => Output::Error(JavaScriptError {
-            exception: e,
-            stack_trace: String::from(""),
-        }),
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+// Pointer and length variants of `call`. The parameters are tagged, so their strings are taken by length
+// as well.
+#[no_mangle]
+pub unsafe extern "C" fn call_utf8(
+    v8_facade_ptr: *mut V8Facade,
+    func_name: *const u8,
+    func_name_length: usize,
+    parameters: *const TaggedPrimitive,
+    parameter_count: usize,
+) -> *mut PrimitiveResult {
+    let func_name = utf8_from_raw_parts(func_name, func_name_length);
+
+    let parameters = tagged_parameters(parameters, parameter_count);
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match parameters.and_then(|parameters| instance.call(func_name, parameters)) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn call_utf16(
+    v8_facade_ptr: *mut V8Facade,
+    func_name: *const u16,
+    func_name_length: usize,
+    parameters: *const TaggedPrimitive,
+    parameter_count: usize,
+) -> *mut PrimitiveResult {
+    let func_name = String::from_utf16_lossy(&utf16_from_raw_parts(func_name, func_name_length));
+
+    let parameters = tagged_parameters(parameters, parameter_count);
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match parameters
+        .and_then(|parameters| instance.call_utf16(func_name, parameters, ValueEncoding::Json))
+    {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
     };
 
     PrimitiveResult::from_output(result).into_raw()
@@ -148,7 +594,7 @@ pub unsafe extern "C" fn begin_call(
     let parameters: &[Primitive] = std::slice::from_raw_parts(parameters, parameter_count);
     let parameters = parameters
         .iter()
-        .map(|p| FunctionParameter::from(p))
+        .map(FunctionParameter::from)
         .collect();
 
     let instance = {
@@ -165,6 +611,347 @@ pub unsafe extern "C" fn begin_call(
         .unwrap();
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn exec_tagged(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const u8,
+    script_length: usize,
+) -> *mut TaggedPrimitive {
+    let script = utf8_from_raw_parts(script, script_length);
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.run(script).unwrap();
+
+    TaggedPrimitive::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn call_tagged(
+    v8_facade_ptr: *mut V8Facade,
+    func_name: *const u8,
+    func_name_length: usize,
+    parameters: *const TaggedPrimitive,
+    parameter_count: usize,
+) -> *mut TaggedPrimitive {
+    let func_name = utf8_from_raw_parts(func_name, func_name_length);
+
+    let parameters = tagged_parameters(parameters, parameter_count);
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match parameters.and_then(|parameters| instance.call(func_name, parameters)) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    TaggedPrimitive::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn check_syntax(
+    v8_facade_ptr: *mut V8Facade,
+    script: *const c_char,
+    kind: SourceKind,
+) -> *mut SyntaxCheckResult {
+    let script = CStr::from_ptr(script).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.check_syntax(script, kind);
+
+    SyntaxCheckResult::from_result(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn clear_timers(v8_facade_ptr: *mut V8Facade) {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    instance.clear_timers().unwrap();
+}
+
+// Unhandled promise rejections are handed to `on_rejection` instead of failing the request that caused
+// them, as are exceptions from timers that fire between requests. Pass null to stop. It's called on the
+// worker thread and the error is freed with `free_javascript_error`.
+#[no_mangle]
+pub unsafe extern "C" fn set_unhandled_rejection_callback(
+    v8_facade_ptr: *mut V8Facade,
+    on_rejection: Option<extern "C" fn(*mut UnsafeJavaScriptError)>,
+) {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    match on_rejection {
+        Some(on_rejection) => instance
+            .set_unhandled_rejection_handler(move |e| {
+                on_rejection(UnsafeJavaScriptError::from_error(e).into_raw());
+            })
+            .unwrap(),
+
+        None => instance.clear_unhandled_rejection_handler().unwrap(),
+    }
+}
+
+// Modules that aren't cached yet are loaded by calling `load_module` with their resolved specifier, on
+// the worker thread. It returns the module's source, or null when there's no such module. The source is
+// copied before the call returns and stays owned by the host. Pass null to remove the loader.
+#[no_mangle]
+pub unsafe extern "C" fn set_module_loader(
+    v8_facade_ptr: *mut V8Facade,
+    load_module: Option<extern "C" fn(*const c_char) -> *const c_char>,
+) {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    match load_module {
+        Some(load_module) => instance
+            .set_module_loader(move |specifier| {
+                let specifier = CString::new(specifier).ok()?;
+                let source = load_module(specifier.as_ptr());
+
+                if source.is_null() {
+                    None
+                } else {
+                    Some(CStr::from_ptr(source).to_string_lossy().into_owned())
+                }
+            })
+            .unwrap(),
+
+        None => instance.clear_module_loader().unwrap(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn register_module(
+    v8_facade_ptr: *mut V8Facade,
+    specifier: *const c_char,
+    script: *const c_char,
+) {
+    let specifier = CStr::from_ptr(specifier).to_string_lossy().into_owned();
+    let script = CStr::from_ptr(script).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    instance.register_module(specifier, script).unwrap();
+}
+
+// Returns null when the import map was applied, otherwise why it wasn't, to be freed with `free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn set_import_map(
+    v8_facade_ptr: *mut V8Facade,
+    import_map: *const c_char,
+) -> *mut c_char {
+    let import_map = CStr::from_ptr(import_map).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    match instance.set_import_map(import_map) {
+        Ok(()) => std::ptr::null_mut(),
+        Err(e) => primitive_result::into_c_string(e).into_raw(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn set_microtask_policy(
+    v8_facade_ptr: *mut V8Facade,
+    policy: MicrotaskPolicy,
+) {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    instance.set_microtask_policy(policy).unwrap();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn perform_microtask_checkpoint(
+    v8_facade_ptr: *mut V8Facade,
+) -> *mut PrimitiveResult {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.perform_microtask_checkpoint().unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enqueue_microtask(
+    v8_facade_ptr: *mut V8Facade,
+    func_name: *const c_char,
+    parameters: *const Primitive,
+    parameter_count: usize,
+) -> *mut PrimitiveResult {
+    let func_name = CStr::from_ptr(func_name).to_string_lossy().into_owned();
+
+    let parameters: &[Primitive] = std::slice::from_raw_parts(parameters, parameter_count);
+    let parameters = parameters
+        .iter()
+        .map(FunctionParameter::from)
+        .collect();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match instance.enqueue_microtask(func_name, parameters) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+// The module's bytes are copied, the host keeps ownership of them and of the imports.
+#[no_mangle]
+pub unsafe extern "C" fn load_wasm(
+    v8_facade_ptr: *mut V8Facade,
+    name: *const c_char,
+    bytes: *const u8,
+    bytes_length: usize,
+    imports: *const UnsafeWasmImport,
+    import_count: usize,
+) -> *mut PrimitiveResult {
+    let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+
+    let bytes = if bytes.is_null() || bytes_length == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(bytes, bytes_length).to_vec()
+    };
+
+    let imports = if imports.is_null() || import_count == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(imports, import_count)
+            .iter()
+            .map(|i| i.to_import())
+            .collect()
+    };
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match instance.load_wasm(name, bytes, imports) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wasm_exports(
+    v8_facade_ptr: *mut V8Facade,
+    name: *const c_char,
+) -> *mut PrimitiveResult {
+    let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match instance.wasm_exports(name) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn call_wasm(
+    v8_facade_ptr: *mut V8Facade,
+    name: *const c_char,
+    func_name: *const c_char,
+    parameters: *const Primitive,
+    parameter_count: usize,
+) -> *mut PrimitiveResult {
+    let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+    let func_name = CStr::from_ptr(func_name).to_string_lossy().into_owned();
+
+    let parameters: &[Primitive] = std::slice::from_raw_parts(parameters, parameter_count);
+    let parameters = parameters
+        .iter()
+        .map(FunctionParameter::from)
+        .collect();
+
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = match instance.call_wasm(name, func_name, parameters) {
+        Ok(o) => o,
+        Err(e) => Output::Error(JavaScriptError::new(e, String::from(""))),
+    };
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lockdown(v8_facade_ptr: *mut V8Facade) -> *mut PrimitiveResult {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.lockdown().unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_globals(v8_facade_ptr: *mut V8Facade) -> *mut PrimitiveResult {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    let result = instance.globals().unwrap();
+
+    PrimitiveResult::from_output(result).into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn set_time(v8_facade_ptr: *mut V8Facade, time: f64) {
+    let instance = {
+        assert!(!v8_facade_ptr.is_null());
+        &mut *v8_facade_ptr
+    };
+
+    instance.set_time(time).unwrap();
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn get_heap_statistics(
     v8_facade_ptr: *mut V8Facade,
@@ -213,6 +1000,27 @@ pub unsafe extern "C" fn free_primitive_result(primitive_result_ptr: *mut Primit
     PrimitiveResult::free_raw(primitive_result_ptr);
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn free_tagged_primitive(tagged_primitive_ptr: *mut TaggedPrimitive) {
+    if !tagged_primitive_ptr.is_null() {
+        TaggedPrimitive::free_raw(tagged_primitive_ptr);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn free_syntax_check_result(syntax_check_result_ptr: *mut SyntaxCheckResult) {
+    if !syntax_check_result_ptr.is_null() {
+        SyntaxCheckResult::free_raw(syntax_check_result_ptr);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn free_javascript_error(javascript_error_ptr: *mut UnsafeJavaScriptError) {
+    if !javascript_error_ptr.is_null() {
+        UnsafeJavaScriptError::free_raw(javascript_error_ptr);
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn free_heap_stats(heap_stats_ptr: *mut V8HeapStatistics) {
     if !heap_stats_ptr.is_null() {
@@ -0,0 +1,660 @@
+use std::{collections::HashMap, convert::TryFrom};
+
+use crate::v8facade::{exception_message, module_origin, new_string};
+
+// Hands back the source text of the module with a resolved specifier, or `None` if there isn't one.
+pub(crate) type ModuleLoader = Box<dyn FnMut(&str) -> Option<String> + Send>;
+
+// Modules are cached by their resolved specifier, so a module that's imported from several places,
+// statically or with `import()`, is only ever evaluated once. Anything not in the cache comes from the
+// registry of modules the host registered up front, and failing that from the host's loader, which
+// hands back its source text.
+#[derive(Default)]
+struct Modules {
+    loader: Option<ModuleLoader>,
+    registry: HashMap<String, String>,
+    import_map: ImportMap,
+    cache: HashMap<(String, ModuleType), v8::Global<v8::Module>>,
+
+    // The default exports of JSON and text modules that haven't been evaluated yet.
+    synthetic_exports: Vec<(v8::Global<v8::Module>, v8::Global<v8::Value>)>,
+}
+
+// Picked with an import assertion, e.g. `import config from './config.json' assert { type: 'json' }`,
+// or the newer attribute `with { type: 'json' }`, see `with_as_assert`. `import()` only reads them from
+// the `assert` of its options, V8 11.0 ignores a `with` there. JSON and text modules have a single
+// default export, the parsed JSON or the text itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ModuleType {
+    JavaScript,
+    Json,
+    Text,
+}
+
+// A browser style import map, e.g. `{"imports": {"lodash": "lib/lodash.js", "utils/": "lib/utils/"}}`.
+// Keys ending in `/` remap every specifier they're a prefix of. `scopes` holds maps that only apply to
+// modules whose names start with the scope's key. Both are kept longest key first, which is the one
+// that wins when several match.
+#[derive(Default)]
+struct ImportMap {
+    imports: Vec<(String, String)>,
+    scopes: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl ImportMap {
+    fn remap(&self, specifier: &str, referrer: &str) -> Option<String> {
+        self.scopes
+            .iter()
+            .filter(|(scope, _)| {
+                referrer == scope || (scope.ends_with('/') && referrer.starts_with(scope.as_str()))
+            })
+            .find_map(|(_, imports)| remap(imports, specifier))
+            .or_else(|| remap(&self.imports, specifier))
+    }
+}
+
+fn remap(imports: &[(String, String)], specifier: &str) -> Option<String> {
+    imports.iter().find_map(|(key, address)| {
+        if key == specifier {
+            Some(resolve_path(address, ""))
+        } else if key.ends_with('/') && specifier.starts_with(key.as_str()) {
+            Some(resolve_path(
+                &format!("{}{}", address, &specifier[key.len()..]),
+                "",
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn install(isolate: &mut v8::Isolate) {
+    isolate.set_slot(Modules::default());
+    isolate.set_host_import_module_dynamically_callback(import_dynamically);
+}
+
+pub fn set_loader(isolate: &mut v8::Isolate, loader: Option<ModuleLoader>) {
+    if let Some(modules) = isolate.get_slot_mut::<Modules>() {
+        modules.loader = loader;
+    }
+}
+
+// Registering a module again replaces its source for imports that haven't loaded it yet.
+pub fn register(isolate: &mut v8::Isolate, specifier: &str, source: String) {
+    if let Some(modules) = isolate.get_slot_mut::<Modules>() {
+        let name = resolve_path(specifier, "");
+
+        modules.cache.retain(|(cached, _), _| *cached != name);
+        modules.registry.insert(name, source);
+    }
+}
+
+pub fn set_import_map(scope: &mut v8::HandleScope, json: &str) -> Result<(), String> {
+    let json = new_string(scope, json)?;
+
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let import_map = match v8::json::parse(tc, json) {
+        Some(import_map) => import_map,
+        None => {
+            return Err(format!(
+                "There was an issue parsing the import map: {}",
+                exception_message(tc)
+            ))
+        }
+    };
+
+    let import_map = v8::Local::<v8::Object>::try_from(import_map)
+        .map_err(|_| String::from("The import map must be an object."))?;
+
+    let imports = match get_property(tc, import_map, "imports")? {
+        Some(imports) => specifier_map(tc, imports)?,
+        None => Vec::new(),
+    };
+
+    let mut scopes = Vec::new();
+
+    if let Some(value) = get_property(tc, import_map, "scopes")? {
+        for (scope, imports) in entries(tc, value)? {
+            scopes.push((scope, specifier_map(tc, imports)?));
+        }
+
+        scopes.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+    }
+
+    if let Some(modules) = tc.get_slot_mut::<Modules>() {
+        modules.import_map = ImportMap { imports, scopes };
+    }
+
+    Ok(())
+}
+
+fn get_property<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<v8::Object>,
+    name: &str,
+) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
+    let key = new_string(scope, name)?;
+
+    Ok(object
+        .get(scope, key.into())
+        .filter(|value| !value.is_undefined()))
+}
+
+fn entries<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+) -> Result<Vec<(String, v8::Local<'s, v8::Value>)>, String> {
+    let object = v8::Local::<v8::Object>::try_from(value)
+        .map_err(|_| String::from("The import map's `imports` and `scopes` must be objects."))?;
+
+    let keys = object
+        .get_own_property_names(scope, Default::default())
+        .ok_or_else(|| String::from("Couldn't read the import map."))?;
+
+    let mut entries = Vec::with_capacity(keys.length() as usize);
+
+    for i in 0..keys.length() {
+        // JSON.parse only ever produces plain objects, so nothing here can run script.
+        let key = keys.get_index(scope, i).unwrap();
+        let value = object.get(scope, key).unwrap();
+
+        entries.push((key.to_rust_string_lossy(scope), value));
+    }
+
+    Ok(entries)
+}
+
+fn specifier_map<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: v8::Local<'s, v8::Value>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut imports = Vec::new();
+
+    for (key, address) in entries(scope, value)? {
+        if !address.is_string() {
+            return Err(format!(
+                "The import map's address for `{}` must be a string.",
+                key
+            ));
+        }
+
+        imports.push((key, address.to_rust_string_lossy(scope)));
+    }
+
+    imports.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+
+    Ok(imports)
+}
+
+pub fn clear(isolate: &mut v8::Isolate) {
+    if let Some(modules) = isolate.get_slot_mut::<Modules>() {
+        modules.cache.clear();
+        modules.synthetic_exports.clear();
+    }
+}
+
+// Compiles `source` as the module `name`, links everything it imports and evaluates it. Hands back the
+// module and the promise its evaluation settles, or `None` with an exception on the scope.
+pub fn evaluate<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    source: &str,
+) -> Option<(v8::Local<'s, v8::Module>, v8::Local<'s, v8::Value>)> {
+    let module = compile(scope, &resolve_path(name, ""), source)?;
+
+    module.instantiate_module(scope, resolve_import)?;
+
+    let result = module.evaluate(scope)?;
+
+    Some((module, result))
+}
+
+// The name a module is cached under. Relative specifiers are resolved against the name of the module
+// importing them, then the import map gets a chance to remap the result.
+fn resolve(isolate: &v8::Isolate, specifier: &str, referrer: &str) -> String {
+    let resolved = resolve_path(specifier, referrer);
+
+    isolate
+        .get_slot::<Modules>()
+        .and_then(|modules| modules.import_map.remap(&resolved, referrer))
+        .unwrap_or(resolved)
+}
+
+// The way a browser resolves relative specifiers against a module's URL. Anything else is used as is.
+fn resolve_path(specifier: &str, referrer: &str) -> String {
+    if specifier.starts_with("./") || specifier.starts_with("../") {
+        match referrer.rfind('/') {
+            Some(i) => normalize(&format!("{}/{}", &referrer[..i], specifier)),
+            None => normalize(specifier),
+        }
+    } else if specifier.starts_with('/') {
+        normalize(specifier)
+    } else {
+        String::from(specifier)
+    }
+}
+
+// Drops `.` segments and applies `..` ones, never going above the root.
+pub(crate) fn normalize(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    if path.starts_with('/') {
+        format!("/{}", segments.join("/"))
+    } else {
+        segments.join("/")
+    }
+}
+
+pub(crate) fn throw_error(scope: &mut v8::HandleScope, message: &str) {
+    if let Ok(message) = new_string(scope, message) {
+        let exception = v8::Exception::error(scope, message);
+
+        scope.throw_exception(exception);
+    }
+}
+
+fn throw_type_error(scope: &mut v8::HandleScope, message: &str) {
+    if let Ok(message) = new_string(scope, message) {
+        let exception = v8::Exception::type_error(scope, message);
+
+        scope.throw_exception(exception);
+    }
+}
+
+// Import assertions come as key and value pairs, followed by where they are in the source for static
+// imports.
+fn module_type(
+    scope: &mut v8::HandleScope,
+    assertions: v8::Local<v8::FixedArray>,
+    entry_length: usize,
+) -> Result<ModuleType, String> {
+    for i in (0..assertions.length()).step_by(entry_length) {
+        if assertion(scope, assertions, i).as_deref() != Some("type") {
+            continue;
+        }
+
+        return match assertion(scope, assertions, i + 1).as_deref() {
+            Some("json") => Ok(ModuleType::Json),
+            Some("text") => Ok(ModuleType::Text),
+            other => Err(format!(
+                "Import assertion type \"{}\" is unsupported.",
+                other.unwrap_or("")
+            )),
+        };
+    }
+
+    Ok(ModuleType::JavaScript)
+}
+
+fn assertion(
+    scope: &mut v8::HandleScope,
+    assertions: v8::Local<v8::FixedArray>,
+    index: usize,
+) -> Option<String> {
+    let assertion = assertions.get(scope, index)?;
+    let assertion = v8::Local::<v8::Value>::try_from(assertion).ok()?;
+
+    Some(assertion.to_rust_string_lossy(scope))
+}
+
+fn compile<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    source: &str,
+) -> Option<v8::Local<'s, v8::Module>> {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    if let Some(module) = compile_source(tc, name, source) {
+        return Some(module);
+    }
+
+    let rewritten = with_as_assert(source);
+
+    if rewritten != source {
+        tc.reset();
+
+        if let Some(module) = compile_source(tc, name, &rewritten) {
+            return Some(module);
+        }
+
+        // The error that's reported is about the source as it was written.
+        tc.reset();
+        compile_source(tc, name, source);
+    }
+
+    tc.rethrow();
+
+    None
+}
+
+fn compile_source<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    source: &str,
+) -> Option<v8::Local<'s, v8::Module>> {
+    let (source, origin) = match (new_string(scope, source), module_origin(scope, name)) {
+        (Ok(source), Ok(origin)) => (source, origin),
+        (Err(error), _) | (_, Err(error)) => {
+            throw_error(scope, &error);
+            return None;
+        }
+    };
+
+    let source = v8::script_compiler::Source::new(source, Some(&origin));
+    let module = v8::script_compiler::compile_module(scope, source)?;
+
+    let cached = v8::Global::new(scope, module);
+
+    if let Some(modules) = scope.get_slot_mut::<Modules>() {
+        modules
+            .cache
+            .insert((String::from(name), ModuleType::JavaScript), cached);
+    }
+
+    Some(module)
+}
+
+// V8 11.0 predates import attributes and only parses the import assertions they replaced, which take
+// the same `{ type: 'json' }`. A module that doesn't compile is tried again with every `with` that
+// follows the specifier of a static import or export turned into an `assert`.
+fn with_as_assert(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut rewritten = String::with_capacity(source.len());
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if matches!(bytes.get(i + 1), Some(b'/') | Some(b'*')) => i = skip_blank(bytes, i),
+
+            quote @ (b'\'' | b'"' | b'`') => {
+                i += 1;
+
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+
+                i = (i + 1).min(bytes.len());
+
+                let with = skip_blank(bytes, i);
+                let brace = skip_blank(bytes, with + 4);
+
+                if quote != b'`'
+                    && bytes[with..].starts_with(b"with")
+                    && bytes.get(brace) == Some(&b'{')
+                {
+                    rewritten.push_str(&source[copied..with]);
+                    rewritten.push_str("assert");
+                    copied = with + 4;
+                    i = brace;
+                }
+            }
+
+            _ => i += 1,
+        }
+    }
+
+    rewritten.push_str(&source[copied..]);
+
+    rewritten
+}
+
+// Skips whitespace and comments.
+fn skip_blank(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+        } else if bytes[i..].starts_with(b"//") {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        } else if bytes[i..].starts_with(b"/*") {
+            i = bytes[i + 2..]
+                .windows(2)
+                .position(|w| w == b"*/")
+                .map_or(bytes.len(), |end| i + end + 4);
+        } else {
+            break;
+        }
+    }
+
+    i
+}
+
+fn synthesize<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    source: &str,
+    module_type: ModuleType,
+) -> Option<v8::Local<'s, v8::Module>> {
+    let (text, module_name) = match (new_string(scope, source), new_string(scope, name)) {
+        (Ok(text), Ok(module_name)) => (text, module_name),
+        (Err(error), _) | (_, Err(error)) => {
+            throw_error(scope, &error);
+            return None;
+        }
+    };
+
+    // Invalid JSON fails the import rather than the evaluation, the same as in browsers.
+    let value = match module_type {
+        ModuleType::Json => v8::json::parse(scope, text)?,
+        _ => text.into(),
+    };
+
+    let default = v8::String::new(scope, "default")?;
+
+    let module =
+        v8::Module::create_synthetic_module(scope, module_name, &[default], evaluate_synthetic);
+
+    let cached = v8::Global::new(scope, module);
+    let pending = (
+        v8::Global::new(scope, module),
+        v8::Global::new(scope, value),
+    );
+
+    if let Some(modules) = scope.get_slot_mut::<Modules>() {
+        modules
+            .cache
+            .insert((String::from(name), module_type), cached);
+        modules.synthetic_exports.push(pending);
+    }
+
+    Some(module)
+}
+
+fn evaluate_synthetic<'s>(
+    context: v8::Local<'s, v8::Context>,
+    module: v8::Local<'s, v8::Module>,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let scope = &mut unsafe { v8::CallbackScope::new(context) };
+
+    let value = {
+        let modules = scope.get_slot_mut::<Modules>()?;
+        let i = modules
+            .synthetic_exports
+            .iter()
+            .position(|(pending, _)| *pending == module)?;
+
+        modules.synthetic_exports.swap_remove(i).1
+    };
+
+    let value = v8::Local::new(scope, value);
+    let default = v8::String::new(scope, "default")?;
+
+    module.set_synthetic_module_export(scope, default, value)?;
+
+    Some(v8::undefined(scope).into())
+}
+
+fn load<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    specifier: &str,
+    referrer: &str,
+    module_type: ModuleType,
+) -> Option<v8::Local<'s, v8::Module>> {
+    let name = resolve(scope, specifier, referrer);
+
+    let cached = scope
+        .get_slot::<Modules>()
+        .and_then(|modules| modules.cache.get(&(name.clone(), module_type)))
+        .cloned();
+
+    if let Some(cached) = cached {
+        return Some(v8::Local::new(scope, cached));
+    }
+
+    let source =
+        scope
+            .get_slot_mut::<Modules>()
+            .and_then(|modules| match modules.registry.get(&name) {
+                Some(source) => Some(source.clone()),
+                None => modules.loader.as_mut().and_then(|loader| loader(&name)),
+            });
+
+    match source {
+        Some(source) if module_type == ModuleType::JavaScript => compile(scope, &name, &source),
+        Some(source) => synthesize(scope, &name, &source, module_type),
+        None => {
+            let message = if referrer.is_empty() {
+                format!("Cannot find module '{}'", specifier)
+            } else {
+                format!(
+                    "Cannot find module '{}' imported from '{}'",
+                    specifier, referrer
+                )
+            };
+
+            throw_error(scope, &message);
+
+            None
+        }
+    }
+}
+
+fn name_of(isolate: &v8::Isolate, module: v8::Local<v8::Module>) -> String {
+    isolate
+        .get_slot::<Modules>()
+        .and_then(|modules| {
+            modules
+                .cache
+                .iter()
+                .find(|(_, cached)| **cached == module)
+                .map(|((name, _), _)| name.clone())
+        })
+        .unwrap_or_default()
+}
+
+fn resolve_import<'s>(
+    context: v8::Local<'s, v8::Context>,
+    specifier: v8::Local<'s, v8::String>,
+    import_assertions: v8::Local<'s, v8::FixedArray>,
+    referrer: v8::Local<'s, v8::Module>,
+) -> Option<v8::Local<'s, v8::Module>> {
+    let scope = &mut unsafe { v8::CallbackScope::new(context) };
+
+    let module_type = match module_type(scope, import_assertions, 3) {
+        Ok(module_type) => module_type,
+        Err(error) => {
+            throw_type_error(scope, &error);
+            return None;
+        }
+    };
+
+    let specifier = specifier.to_rust_string_lossy(scope);
+    let referrer = name_of(scope, referrer);
+
+    load(scope, &specifier, &referrer, module_type)
+}
+
+// Loaders are synchronous, so the module is loaded, linked and evaluated before `import()` returns. The
+// promise still settles in a later microtask, once evaluation has finished.
+fn import_dynamically<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    _host_defined_options: v8::Local<'s, v8::Data>,
+    resource_name: v8::Local<'s, v8::Value>,
+    specifier: v8::Local<'s, v8::String>,
+    import_assertions: v8::Local<'s, v8::FixedArray>,
+) -> Option<v8::Local<'s, v8::Promise>> {
+    let resolver = v8::PromiseResolver::new(scope)?;
+    let promise = resolver.get_promise(scope);
+
+    let specifier = specifier.to_rust_string_lossy(scope);
+
+    // Scripts run by the facade have no name, so their imports resolve from the root.
+    let referrer = if resource_name.is_string() {
+        resource_name.to_rust_string_lossy(scope)
+    } else {
+        String::from("")
+    };
+
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let namespace = match module_type(tc, import_assertions, 2) {
+        Ok(module_type) => import(tc, &specifier, &referrer, module_type),
+        Err(error) => {
+            throw_type_error(tc, &error);
+            None
+        }
+    };
+
+    match namespace {
+        Some(namespace) => resolver.resolve(tc, namespace),
+        None => {
+            let exception = match tc.exception() {
+                Some(exception) => exception,
+                None => v8::undefined(tc).into(),
+            };
+
+            tc.reset();
+            resolver.reject(tc, exception)
+        }
+    };
+
+    Some(promise)
+}
+
+// A promise for the module's namespace, settled once its evaluation is.
+fn import<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    specifier: &str,
+    referrer: &str,
+    module_type: ModuleType,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let module = load(scope, specifier, referrer, module_type)?;
+
+    module.instantiate_module(scope, resolve_import)?;
+
+    // Evaluating a module again hands back the promise from the first time.
+    let evaluated = v8::Local::<v8::Promise>::try_from(module.evaluate(scope)?).ok()?;
+
+    let namespace = v8::Local::new(scope, module.get_module_namespace());
+
+    let on_fulfilled = v8::Function::builder(return_data)
+        .data(namespace)
+        .build(scope)?;
+
+    evaluated
+        .then(scope, on_fulfilled)
+        .map(|namespace| namespace.into())
+}
+
+fn return_data(
+    _scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    rv.set(args.data());
+}
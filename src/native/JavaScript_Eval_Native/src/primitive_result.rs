@@ -2,11 +2,154 @@ use std::{ffi::CString, os::raw::c_char, ptr};
 
 use crate::v8facade::{JavaScriptError, JavaScriptResult, Output};
 
+// Interior NULs can't be represented in a C string, they're dropped rather than failing the whole result.
+pub(crate) fn into_c_string(string: String) -> CString {
+    CString::new(string).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|b| *b != 0);
+
+        CString::new(bytes).unwrap_or_default()
+    })
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct UnsafeJavaScriptError {
     pub exception: *mut c_char,
     pub stack_trace: *mut c_char,
+
+    pub name: *mut c_char,
+    pub message: *mut c_char,
+
+    // Null when there is no cause.
+    pub cause: *mut UnsafeJavaScriptError,
+
+    // `errors_length` inner errors of an AggregateError, null when there are none.
+    pub errors: *mut UnsafeJavaScriptError,
+    pub errors_length: usize,
+
+    // JSON, null when the thrown value had no properties to report.
+    pub properties: *mut c_char,
+}
+
+impl UnsafeJavaScriptError {
+    pub fn from_error(javascript_error: JavaScriptError) -> UnsafeJavaScriptError {
+        let exception = into_c_string(javascript_error.exception).into_raw();
+        let stack_trace = into_c_string(javascript_error.stack_trace).into_raw();
+        let name = into_c_string(javascript_error.name).into_raw();
+        let message = into_c_string(javascript_error.message).into_raw();
+
+        let properties = match javascript_error.properties {
+            Some(properties) => into_c_string(properties).into_raw(),
+            None => ptr::null_mut(),
+        };
+
+        let cause = match javascript_error.cause {
+            Some(cause) => UnsafeJavaScriptError::from_error(*cause).into_raw(),
+            None => ptr::null_mut(),
+        };
+
+        let errors_length = javascript_error.errors.len();
+
+        let errors = if errors_length > 0 {
+            let errors: Box<[UnsafeJavaScriptError]> = javascript_error
+                .errors
+                .into_iter()
+                .map(UnsafeJavaScriptError::from_error)
+                .collect();
+
+            Box::into_raw(errors) as *mut UnsafeJavaScriptError
+        } else {
+            ptr::null_mut()
+        };
+
+        UnsafeJavaScriptError {
+            exception,
+            stack_trace,
+            name,
+            message,
+            cause,
+            errors,
+            errors_length,
+            properties,
+        }
+    }
+
+    pub fn into_raw(self) -> *mut UnsafeJavaScriptError {
+        Box::into_raw(Box::new(self))
+    }
+
+    pub unsafe fn free_raw(raw_error: *mut UnsafeJavaScriptError) {
+        let error = Box::from_raw(raw_error);
+
+        error.free_fields();
+    }
+
+    unsafe fn free_fields(&self) {
+        drop(CString::from_raw(self.exception));
+        drop(CString::from_raw(self.stack_trace));
+        drop(CString::from_raw(self.name));
+        drop(CString::from_raw(self.message));
+
+        if !self.properties.is_null() {
+            drop(CString::from_raw(self.properties));
+        }
+
+        if !self.cause.is_null() {
+            UnsafeJavaScriptError::free_raw(self.cause);
+        }
+
+        if !self.errors.is_null() {
+            let errors = Box::from_raw(ptr::slice_from_raw_parts_mut(
+                self.errors,
+                self.errors_length,
+            ));
+
+            for error in errors.iter() {
+                error.free_fields();
+            }
+        }
+    }
+}
+
+// A Rust owned byte buffer handed across the FFI boundary. It is released along with the
+// `PrimitiveResult` that carries it, the host must copy the bytes out before freeing that result.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ByteBuffer {
+    pub data: *mut u8,
+    pub length: usize,
+}
+
+impl ByteBuffer {
+    pub fn from_vec(bytes: Vec<u8>) -> ByteBuffer {
+        let bytes = bytes.into_boxed_slice();
+        let length = bytes.len();
+
+        ByteBuffer {
+            data: Box::into_raw(bytes) as *mut u8,
+            length,
+        }
+    }
+
+    pub fn into_raw(self) -> *mut ByteBuffer {
+        Box::into_raw(Box::new(self))
+    }
+
+    pub unsafe fn free_raw(raw_byte_buffer: *mut ByteBuffer) {
+        let byte_buffer = Box::from_raw(raw_byte_buffer);
+
+        byte_buffer.free_data();
+    }
+
+    pub unsafe fn free_data(self) {
+        if !self.data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                self.data,
+                self.length,
+            )));
+        }
+    }
 }
 
 #[repr(C)]
@@ -26,10 +169,19 @@ pub struct PrimitiveResult {
     pub object_value: *mut c_char,
 
     pub error: *mut UnsafeJavaScriptError,
+
+    pub serialized_value: *mut ByteBuffer,
+    pub binary_value: *mut ByteBuffer,
+
+    // `string_value` is NUL terminated for older hosts but may also contain NULs, its length in
+    // bytes (without the terminator) is the authoritative one.
+    pub string_value_length: usize,
+
+    pub utf16_string_value: *mut u16,
+    pub utf16_string_value_length: usize,
 }
 
 impl PrimitiveResult {
-
     pub fn blank() -> PrimitiveResult {
         PrimitiveResult {
             number_value: 0.0,
@@ -42,6 +194,11 @@ impl PrimitiveResult {
             array_value: ptr::null_mut(),
             object_value: ptr::null_mut(),
             error: ptr::null_mut(),
+            serialized_value: ptr::null_mut(),
+            binary_value: ptr::null_mut(),
+            string_value_length: 0,
+            utf16_string_value: ptr::null_mut(),
+            utf16_string_value_length: 0,
         }
     }
 
@@ -78,8 +235,27 @@ impl PrimitiveResult {
     pub fn create_for_string(string: String) -> PrimitiveResult {
         let blank_result = PrimitiveResult::blank();
 
+        let mut bytes = string.into_bytes();
+        let string_value_length = bytes.len();
+        bytes.push(0);
+
+        PrimitiveResult {
+            string_value: Box::into_raw(bytes.into_boxed_slice()) as *mut c_char,
+            string_value_length,
+            ..blank_result
+        }
+    }
+
+    pub fn create_for_utf16_string(string: Vec<u16>) -> PrimitiveResult {
+        let blank_result = PrimitiveResult::blank();
+
+        let mut units = string;
+        let utf16_string_value_length = units.len();
+        units.push(0);
+
         PrimitiveResult {
-            string_value: CString::new(string).unwrap().into_raw(),
+            utf16_string_value: Box::into_raw(units.into_boxed_slice()) as *mut u16,
+            utf16_string_value_length,
             ..blank_result
         }
     }
@@ -88,7 +264,7 @@ impl PrimitiveResult {
         let blank_result = PrimitiveResult::blank();
 
         PrimitiveResult {
-            array_value: CString::new(array).unwrap().into_raw(),
+            array_value: into_c_string(array).into_raw(),
             ..blank_result
         }
     }
@@ -97,23 +273,41 @@ impl PrimitiveResult {
         let blank_result = PrimitiveResult::blank();
 
         PrimitiveResult {
-            object_value: CString::new(object).unwrap().into_raw(),
+            object_value: into_c_string(object).into_raw(),
             ..blank_result
         }
     }
 
-    pub fn create_for_error(javascript_error: JavaScriptError) -> PrimitiveResult {
-        let exception = CString::new(javascript_error.exception).unwrap().into_raw();
-        let stack_trace = CString::new(javascript_error.stack_trace)
-            .unwrap()
-            .into_raw();
+    pub fn create_for_serialized(bytes: Vec<u8>) -> PrimitiveResult {
+        let blank_result = PrimitiveResult::blank();
 
-        let unsafe_error = UnsafeJavaScriptError {
-            exception,
-            stack_trace,
-        };
+        PrimitiveResult {
+            serialized_value: ByteBuffer::from_vec(bytes).into_raw(),
+            ..blank_result
+        }
+    }
+
+    // The JSON older hosts have always found in `object_value` for values that only later had fields or
+    // variants of their own.
+    pub fn create_for_legacy_object(json: Option<String>) -> PrimitiveResult {
+        let blank_result = PrimitiveResult::blank();
 
-        let unsafe_error = Box::into_raw(Box::new(unsafe_error));
+        PrimitiveResult {
+            object_value: json.map_or(ptr::null_mut(), |json| into_c_string(json).into_raw()),
+            ..blank_result
+        }
+    }
+
+    // Hosts that don't know about `binary_value` still find the value's JSON in `object_value`.
+    pub fn create_for_binary(bytes: Vec<u8>, json: Option<String>) -> PrimitiveResult {
+        PrimitiveResult {
+            binary_value: ByteBuffer::from_vec(bytes).into_raw(),
+            ..PrimitiveResult::create_for_legacy_object(json)
+        }
+    }
+
+    pub fn create_for_error(javascript_error: JavaScriptError) -> PrimitiveResult {
+        let unsafe_error = UnsafeJavaScriptError::from_error(javascript_error).into_raw();
 
         let blank_result = PrimitiveResult::blank();
 
@@ -125,58 +319,52 @@ impl PrimitiveResult {
 
     pub fn from_output(output: Output) -> PrimitiveResult {
         match output {
-            Output::Result(r) => match r {
-                JavaScriptResult::StringValue(s) => {
-                    PrimitiveResult::create_for_string(s)
-                }
-                JavaScriptResult::NumberValue(n) => {
-                    PrimitiveResult::create_for_number(n)
-                }
-                JavaScriptResult::BigIntValue(i) => {
-                    PrimitiveResult::create_for_bigint(i)
-                }
-                JavaScriptResult::BoolValue(b) => {
-                    PrimitiveResult::create_for_bool(b)
-                }
-                JavaScriptResult::ArrayValue(av) => {
-                    PrimitiveResult::create_for_array(av)
-                }
-                JavaScriptResult::ObjectValue(ov) => {
-                    PrimitiveResult::create_for_object(ov)
-                }
-            },
-    
+            Output::Result(r) => PrimitiveResult::from_javascriptresult(r),
+
             Output::Error(e) => PrimitiveResult::create_for_error(e),
-    
-            // You can't get heap statistics out of V8 by invoking script so this result is impossible.
-            Output::HeapStatistics(_) => unreachable!(),
-        }        
+
+            // You can't get heap statistics or syntax diagnostics out of V8 by invoking script so these
+            // results are impossible.
+            Output::HeapStatistics(_) | Output::SyntaxDiagnostics(_) => unreachable!(),
+        }
     }
 
     pub fn from_javascriptresult(result: JavaScriptResult) -> PrimitiveResult {
         match result {
-            JavaScriptResult::ArrayValue(v) => {
-                PrimitiveResult::create_for_array(v)
+            // Older hosts have always seen these as the strings JSON.stringify produced for them.
+            JavaScriptResult::NullValue => PrimitiveResult::create_for_string(String::from("null")),
+            JavaScriptResult::UndefinedValue => {
+                PrimitiveResult::create_for_string(String::from("undefined"))
             }
-            JavaScriptResult::StringValue(v) => {
-                PrimitiveResult::create_for_string(v)
+            JavaScriptResult::ArrayValue(v) => PrimitiveResult::create_for_array(v),
+            JavaScriptResult::StringValue(v) => PrimitiveResult::create_for_string(v),
+            JavaScriptResult::NumberValue(v) => PrimitiveResult::create_for_number(v),
+            JavaScriptResult::BigIntValue(v) => PrimitiveResult::create_for_bigint(v),
+            JavaScriptResult::BoolValue(v) => PrimitiveResult::create_for_bool(v),
+            JavaScriptResult::ObjectValue(v) => PrimitiveResult::create_for_object(v),
+            JavaScriptResult::SerializedValue(v) => PrimitiveResult::create_for_serialized(v),
+            JavaScriptResult::BinaryValue { bytes, json } => {
+                PrimitiveResult::create_for_binary(bytes, json)
             }
-            JavaScriptResult::NumberValue(v) => {
-                PrimitiveResult::create_for_number(v)
+            JavaScriptResult::Utf16StringValue(v) => PrimitiveResult::create_for_utf16_string(v),
+            // There's no dedicated field for these, they're handed back the way `String()` would
+            // describe them.
+            JavaScriptResult::SymbolValue(v) => {
+                PrimitiveResult::create_for_string(format!("Symbol({})", v.unwrap_or_default()))
             }
-            JavaScriptResult::BigIntValue(v) => {
-                PrimitiveResult::create_for_bigint(v)
+            JavaScriptResult::FunctionValue { source, .. } => {
+                PrimitiveResult::create_for_string(source)
             }
-            JavaScriptResult::BoolValue(v) => {
-                PrimitiveResult::create_for_bool(v)
-            }
-            JavaScriptResult::ObjectValue(v) => {
-                PrimitiveResult::create_for_object(v)
+            // Before dates and regexps had kinds of their own they were objects, and that's what older
+            // hosts still get.
+            JavaScriptResult::DateValue { json, .. }
+            | JavaScriptResult::RegExpValue { json, .. } => {
+                PrimitiveResult::create_for_legacy_object(json)
             }
         }
     }
 
-    pub fn into_raw(self: Self) -> *mut PrimitiveResult {
+    pub fn into_raw(self) -> *mut PrimitiveResult {
         Box::into_raw(Box::new(self))
     }
 
@@ -184,24 +372,37 @@ impl PrimitiveResult {
         let primitive_result = Box::from_raw(raw_prim_result);
 
         if !primitive_result.string_value.is_null() {
-            drop(CString::from_raw(primitive_result.string_value));
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                primitive_result.string_value as *mut u8,
+                primitive_result.string_value_length + 1,
+            )));
+        }
+
+        if !primitive_result.utf16_string_value.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                primitive_result.utf16_string_value,
+                primitive_result.utf16_string_value_length + 1,
+            )));
         }
-    
+
         if !primitive_result.array_value.is_null() {
             drop(CString::from_raw(primitive_result.array_value));
         }
-    
+
         if !primitive_result.object_value.is_null() {
             drop(CString::from_raw(primitive_result.object_value));
         }
-    
+
         if !primitive_result.error.is_null() {
-            let error = primitive_result.error;
-    
-            drop(CString::from_raw((*error).exception));
-            drop(CString::from_raw((*error).stack_trace));
-    
-            drop(Box::from_raw(primitive_result.error));
-        }       
-    }
-}
\ No newline at end of file
+            UnsafeJavaScriptError::free_raw(primitive_result.error);
+        }
+
+        if !primitive_result.serialized_value.is_null() {
+            ByteBuffer::free_raw(primitive_result.serialized_value);
+        }
+
+        if !primitive_result.binary_value.is_null() {
+            ByteBuffer::free_raw(primitive_result.binary_value);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::v8facade::{format_stack_trace, JavaScriptError, Output, STACK_TRACE_FRAME_LIMIT};
+
+// V8 reports a promise that's rejected while nothing handles it, and again if a handler is attached
+// later. Whatever is still unhandled once a request is done gets reported: to the host's handler when
+// one is registered, otherwise as the error for that request. The promises are only held until then.
+struct UnhandledRejection {
+    promise: v8::Global<v8::Promise>,
+    reason: v8::Global<v8::Value>,
+
+    // Where the promise was rejected, for reasons that don't carry a stack of their own.
+    frames: Option<String>,
+}
+
+#[derive(Default)]
+struct Rejections {
+    unhandled: Vec<UnhandledRejection>,
+    handler: Option<Box<dyn FnMut(JavaScriptError) + Send>>,
+}
+
+pub fn install(isolate: &mut v8::Isolate) {
+    isolate.set_slot(Rejections::default());
+    isolate.set_promise_reject_callback(on_promise_reject);
+}
+
+pub fn set_handler(
+    isolate: &mut v8::Isolate,
+    handler: Option<Box<dyn FnMut(JavaScriptError) + Send>>,
+) {
+    if let Some(rejections) = isolate.get_slot_mut::<Rejections>() {
+        rejections.handler = handler;
+    }
+}
+
+// For promises the host is already reporting the rejection of, e.g. the one `run_with_await` settles.
+pub fn forget(scope: &mut v8::HandleScope, promise: v8::Local<v8::Promise>) {
+    let unhandled = match scope.get_slot_mut::<Rejections>() {
+        Some(rejections) => std::mem::take(&mut rejections.unhandled),
+        None => return,
+    };
+
+    let unhandled = unhandled
+        .into_iter()
+        .filter(|rejection| {
+            !v8::Local::new(scope, &rejection.promise).strict_equals(promise.into())
+        })
+        .collect();
+
+    if let Some(rejections) = scope.get_slot_mut::<Rejections>() {
+        rejections.unhandled = unhandled;
+    }
+}
+
+// Reports what the request that produced `output` left unhandled. Without a handler they become the
+// request's error, unless it failed anyway. More than one is reported the way an `AggregateError` is,
+// with every rejection in `errors`.
+pub fn report(scope: &mut v8::HandleScope, output: Output) -> Output {
+    let mut errors = take(scope);
+
+    if errors.is_empty() || call_handler(scope, &mut errors) {
+        return output;
+    }
+
+    match output {
+        Output::Result(_) if errors.len() == 1 => Output::Error(errors.remove(0)),
+        Output::Result(_) => Output::Error(aggregate(errors)),
+        output => output,
+    }
+}
+
+fn aggregate(errors: Vec<JavaScriptError>) -> JavaScriptError {
+    let name = String::from("AggregateError");
+    let message = format!("{} promises were rejected and not handled.", errors.len());
+
+    let mut error = JavaScriptError::new(
+        format!("Uncaught (in promise) {}: {}", name, message),
+        String::from(""),
+    );
+
+    error.name = name;
+    error.message = message;
+    error.errors = errors;
+
+    error
+}
+
+// For errors no request is waiting on, e.g. what a timer that fired between inputs threw and the
+// rejections it left unhandled. Only a handler can see these.
+pub fn report_to_handler(scope: &mut v8::HandleScope, thrown: Option<JavaScriptError>) {
+    let mut errors: Vec<JavaScriptError> = thrown.into_iter().chain(take(scope)).collect();
+
+    if !errors.is_empty() {
+        call_handler(scope, &mut errors);
+    }
+}
+
+fn call_handler(isolate: &mut v8::Isolate, errors: &mut Vec<JavaScriptError>) -> bool {
+    let handler = match isolate
+        .get_slot_mut::<Rejections>()
+        .and_then(|rejections| rejections.handler.as_mut())
+    {
+        Some(handler) => handler,
+        None => return false,
+    };
+
+    for error in errors.drain(..) {
+        handler(error);
+    }
+
+    true
+}
+
+fn take(scope: &mut v8::HandleScope) -> Vec<JavaScriptError> {
+    let unhandled = match scope.get_slot_mut::<Rejections>() {
+        Some(rejections) => std::mem::take(&mut rejections.unhandled),
+        None => return Vec::new(),
+    };
+
+    unhandled
+        .into_iter()
+        .map(|rejection| {
+            let reason = v8::Local::new(scope, &rejection.reason);
+
+            let mut error = JavaScriptError::from_value(scope, reason);
+
+            // The same wording browsers use when they log these.
+            error.exception = format!("Uncaught (in promise) {}", error.exception);
+
+            if let Some(frames) = rejection.frames {
+                error.stack_trace = format!("{}{}", error.exception, frames);
+            }
+
+            error
+        })
+        .collect()
+}
+
+extern "C" fn on_promise_reject(message: v8::PromiseRejectMessage) {
+    let scope = &mut unsafe { v8::CallbackScope::new(&message) };
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let promise = message.get_promise();
+
+    match message.get_event() {
+        v8::PromiseRejectEvent::PromiseRejectWithNoHandler => {
+            let reason = match message.get_value() {
+                Some(reason) => reason,
+                None => v8::undefined(scope).into(),
+            };
+
+            // Errors already know where they were created.
+            let frames = if reason.is_native_error() {
+                None
+            } else {
+                v8::StackTrace::current_stack_trace(scope, STACK_TRACE_FRAME_LIMIT as usize)
+                    .filter(|frames| frames.get_frame_count() > 0)
+                    .map(|frames| format_stack_trace(scope, "", frames))
+            };
+
+            let rejection = UnhandledRejection {
+                promise: v8::Global::new(scope, promise),
+                reason: v8::Global::new(scope, reason),
+                frames,
+            };
+
+            if let Some(rejections) = scope.get_slot_mut::<Rejections>() {
+                rejections.unhandled.push(rejection);
+            }
+        }
+
+        v8::PromiseRejectEvent::PromiseHandlerAddedAfterReject => forget(scope, promise),
+
+        _ => {}
+    }
+}
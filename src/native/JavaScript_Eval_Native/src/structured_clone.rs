@@ -0,0 +1,734 @@
+use std::collections::HashSet;
+
+use v8::ValueSerializerHelper;
+
+// The wire format is V8's `ValueSerializer` format (src/objects/value-serializer.cc). V8 11.0 writes
+// version 15, older versions are read the same way for the tags that still exist.
+const LATEST_VERSION: u32 = 15;
+
+// The same limit as for extended JSON, the decoder recurses once per level of nesting.
+const MAX_DEPTH: usize = 512;
+
+mod tag {
+    pub const VERSION: u8 = 0xFF;
+    pub const PADDING: u8 = b'\0';
+    pub const VERIFY_OBJECT_COUNT: u8 = b'?';
+    pub const THE_HOLE: u8 = b'-';
+    pub const UNDEFINED: u8 = b'_';
+    pub const NULL: u8 = b'0';
+    pub const TRUE: u8 = b'T';
+    pub const FALSE: u8 = b'F';
+    pub const INT32: u8 = b'I';
+    pub const UINT32: u8 = b'U';
+    pub const DOUBLE: u8 = b'N';
+    pub const BIGINT: u8 = b'Z';
+    pub const UTF8_STRING: u8 = b'S';
+    pub const ONE_BYTE_STRING: u8 = b'"';
+    pub const TWO_BYTE_STRING: u8 = b'c';
+    pub const OBJECT_REFERENCE: u8 = b'^';
+    pub const BEGIN_JS_OBJECT: u8 = b'o';
+    pub const END_JS_OBJECT: u8 = b'{';
+    pub const BEGIN_SPARSE_JS_ARRAY: u8 = b'a';
+    pub const END_SPARSE_JS_ARRAY: u8 = b'@';
+    pub const BEGIN_DENSE_JS_ARRAY: u8 = b'A';
+    pub const END_DENSE_JS_ARRAY: u8 = b'$';
+    pub const DATE: u8 = b'D';
+    pub const TRUE_OBJECT: u8 = b'y';
+    pub const FALSE_OBJECT: u8 = b'x';
+    pub const NUMBER_OBJECT: u8 = b'n';
+    pub const BIGINT_OBJECT: u8 = b'z';
+    pub const STRING_OBJECT: u8 = b's';
+    pub const REGEXP: u8 = b'R';
+    pub const BEGIN_JS_MAP: u8 = b';';
+    pub const END_JS_MAP: u8 = b':';
+    pub const BEGIN_JS_SET: u8 = b'\'';
+    pub const END_JS_SET: u8 = b',';
+    pub const ARRAY_BUFFER: u8 = b'B';
+    pub const RESIZABLE_ARRAY_BUFFER: u8 = b'~';
+    pub const ARRAY_BUFFER_VIEW: u8 = b'V';
+    pub const ERROR: u8 = b'r';
+}
+
+mod error_tag {
+    pub const EVAL_ERROR_PROTOTYPE: u8 = b'E';
+    pub const RANGE_ERROR_PROTOTYPE: u8 = b'R';
+    pub const REFERENCE_ERROR_PROTOTYPE: u8 = b'F';
+    pub const SYNTAX_ERROR_PROTOTYPE: u8 = b'S';
+    pub const TYPE_ERROR_PROTOTYPE: u8 = b'T';
+    pub const URI_ERROR_PROTOTYPE: u8 = b'U';
+    pub const MESSAGE: u8 = b'm';
+    pub const CAUSE: u8 = b'c';
+    pub const STACK: u8 = b's';
+    pub const END: u8 = b'.';
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayBufferViewKind {
+    Int8Array,
+    Uint8Array,
+    Uint8ClampedArray,
+    Int16Array,
+    Uint16Array,
+    Int32Array,
+    Uint32Array,
+    Float32Array,
+    Float64Array,
+    BigInt64Array,
+    BigUint64Array,
+    DataView,
+}
+
+// A decoded structured-clone value. Every object-like value carries the id V8 assigned to it while
+// serializing, `Reference` points back at an object that was already decoded with that id.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CloneValue {
+    Undefined,
+    Null,
+    Hole,
+    Bool(bool),
+    Number(f64),
+    // Magnitude as little-endian 64 bit words.
+    BigInt {
+        negative: bool,
+        words: Vec<u64>,
+    },
+    String(String),
+
+    Object {
+        id: u32,
+        properties: Vec<(String, CloneValue)>,
+    },
+    Array {
+        id: u32,
+        elements: Vec<CloneValue>,
+        properties: Vec<(String, CloneValue)>,
+    },
+    SparseArray {
+        id: u32,
+        length: u32,
+        properties: Vec<(String, CloneValue)>,
+    },
+    Date {
+        id: u32,
+        epoch_milliseconds: f64,
+    },
+    RegExp {
+        id: u32,
+        pattern: String,
+        flags: String,
+    },
+    Map {
+        id: u32,
+        entries: Vec<(CloneValue, CloneValue)>,
+    },
+    Set {
+        id: u32,
+        values: Vec<CloneValue>,
+    },
+    ArrayBuffer {
+        id: u32,
+        bytes: Vec<u8>,
+    },
+    ArrayBufferView {
+        id: u32,
+        kind: ArrayBufferViewKind,
+        buffer: Box<CloneValue>,
+        byte_offset: u32,
+        byte_length: u32,
+    },
+    BooleanObject {
+        id: u32,
+        value: bool,
+    },
+    NumberObject {
+        id: u32,
+        value: f64,
+    },
+    BigIntObject {
+        id: u32,
+        value: Box<CloneValue>,
+    },
+    StringObject {
+        id: u32,
+        value: String,
+    },
+    Error {
+        id: u32,
+        name: String,
+        message: Option<String>,
+        stack: Option<String>,
+        cause: Option<Box<CloneValue>>,
+    },
+
+    Reference(u32),
+}
+
+struct Serializer;
+
+impl v8::ValueSerializerImpl for Serializer {
+    fn throw_data_clone_error<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        message: v8::Local<'s, v8::String>,
+    ) {
+        let error = v8::Exception::error(scope, message);
+        scope.throw_exception(error);
+    }
+}
+
+pub fn serialize(
+    scope: &mut v8::HandleScope,
+    value: v8::Local<v8::Value>,
+) -> Result<Vec<u8>, String> {
+    let scope = &mut v8::TryCatch::new(scope);
+    let context = scope.get_current_context();
+
+    let (written, bytes) = {
+        let mut serializer = v8::ValueSerializer::new(scope, Box::new(Serializer));
+        serializer.write_header();
+
+        let written = serializer.write_value(context, value);
+
+        (written, serializer.release())
+    };
+
+    match written {
+        Some(true) => Ok(bytes),
+
+        _ => {
+            let exception = if let Some(exception) = scope.exception() {
+                exception.to_rust_string_lossy(scope)
+            } else {
+                String::from("No exception message was present.")
+            };
+
+            Err(format!(
+                "There was an issue serializing the result: {}",
+                exception
+            ))
+        }
+    }
+}
+
+pub fn decode(bytes: &[u8]) -> Result<CloneValue, String> {
+    let mut decoder = Decoder {
+        bytes,
+        position: 0,
+        version: 0,
+        next_id: 0,
+        depth: 0,
+        array_buffer_ids: HashSet::new(),
+    };
+
+    decoder.read_header()?;
+    decoder.read_value()
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    version: u32,
+    next_id: u32,
+    depth: usize,
+    array_buffer_ids: HashSet<u32>,
+}
+
+impl<'a> Decoder<'a> {
+    fn read_header(&mut self) -> Result<(), String> {
+        if self.peek_raw_byte() == Some(tag::VERSION) {
+            self.position += 1;
+            self.version = self.read_varint()?;
+
+            if self.version > LATEST_VERSION {
+                return Err(format!(
+                    "Unsupported structured clone version {}.",
+                    self.version
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn peek_raw_byte(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn read_byte(&mut self) -> Result<u8, String> {
+        let byte = self
+            .peek_raw_byte()
+            .ok_or_else(|| String::from("Unexpected end of structured clone data."))?;
+
+        self.position += 1;
+
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| String::from("Unexpected end of structured clone data."))?;
+
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn skip_padding(&mut self) {
+        while self.peek_raw_byte() == Some(tag::PADDING) {
+            self.position += 1;
+        }
+    }
+
+    fn peek_tag(&mut self) -> Result<u8, String> {
+        loop {
+            self.skip_padding();
+
+            let tag = self
+                .peek_raw_byte()
+                .ok_or_else(|| String::from("Unexpected end of structured clone data."))?;
+
+            if tag == tag::VERIFY_OBJECT_COUNT {
+                self.position += 1;
+                self.read_varint()?;
+            } else {
+                return Ok(tag);
+            }
+        }
+    }
+
+    fn read_tag(&mut self) -> Result<u8, String> {
+        let tag = self.peek_tag()?;
+        self.position += 1;
+
+        Ok(tag)
+    }
+
+    fn read_varint(&mut self) -> Result<u32, String> {
+        Ok(self.read_varint64()? as u32)
+    }
+
+    fn read_varint64(&mut self) -> Result<u64, String> {
+        let mut result = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.read_byte()?;
+
+            if shift < 64 {
+                result |= ((byte & 0x7F) as u64) << shift;
+            }
+
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+    }
+
+    fn read_zigzag(&mut self) -> Result<i32, String> {
+        let value = self.read_varint()?;
+
+        Ok(((value >> 1) as i32) ^ -((value & 1) as i32))
+    }
+
+    fn read_double(&mut self) -> Result<f64, String> {
+        let bytes = self.read_bytes(8)?;
+        let mut buffer = [0u8; 8];
+        buffer.copy_from_slice(bytes);
+
+        Ok(f64::from_le_bytes(buffer))
+    }
+
+    fn read_bigint_contents(&mut self) -> Result<CloneValue, String> {
+        let bitfield = self.read_varint()?;
+        let negative = bitfield & 1 == 1;
+        let byte_length = (bitfield >> 1) as usize;
+
+        let words = self
+            .read_bytes(byte_length)?
+            .chunks(8)
+            .map(|chunk| {
+                let mut buffer = [0u8; 8];
+                buffer[..chunk.len()].copy_from_slice(chunk);
+
+                u64::from_le_bytes(buffer)
+            })
+            .collect();
+
+        Ok(CloneValue::BigInt { negative, words })
+    }
+
+    fn read_string_contents(&mut self, tag: u8) -> Result<String, String> {
+        let length = self.read_varint()? as usize;
+        let bytes = self.read_bytes(length)?;
+
+        match tag {
+            tag::ONE_BYTE_STRING => Ok(bytes.iter().map(|b| *b as char).collect()),
+
+            tag::TWO_BYTE_STRING => {
+                let units: Vec<u16> = bytes
+                    .chunks(2)
+                    .map(|c| u16::from_le_bytes([c[0], *c.get(1).unwrap_or(&0)]))
+                    .collect();
+
+                Ok(String::from_utf16_lossy(&units))
+            }
+
+            _ => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        match self.read_value()? {
+            CloneValue::String(s) => Ok(s),
+            other => Err(format!("Expected a string but found {:?}.", other)),
+        }
+    }
+
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        id
+    }
+
+    fn read_value(&mut self) -> Result<CloneValue, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!(
+                "Values nested more than {} levels deep can't be decoded.",
+                MAX_DEPTH
+            ));
+        }
+
+        self.depth += 1;
+        let value = self.read_value_internal();
+        self.depth -= 1;
+
+        let value = value?;
+
+        // An ArrayBufferView is written right after the buffer it is a view over, or right after a
+        // reference to it when the buffer was already written for another value.
+        let is_array_buffer = match value {
+            CloneValue::ArrayBuffer { .. } => true,
+            CloneValue::Reference(id) => self.array_buffer_ids.contains(&id),
+            _ => false,
+        };
+
+        if is_array_buffer {
+            self.skip_padding();
+
+            if self.peek_raw_byte() == Some(tag::ARRAY_BUFFER_VIEW) {
+                self.position += 1;
+
+                return self.read_array_buffer_view(value);
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn read_value_internal(&mut self) -> Result<CloneValue, String> {
+        let tag = self.read_tag()?;
+
+        match tag {
+            tag::THE_HOLE => Ok(CloneValue::Hole),
+            tag::UNDEFINED => Ok(CloneValue::Undefined),
+            tag::NULL => Ok(CloneValue::Null),
+            tag::TRUE => Ok(CloneValue::Bool(true)),
+            tag::FALSE => Ok(CloneValue::Bool(false)),
+            tag::INT32 => Ok(CloneValue::Number(self.read_zigzag()? as f64)),
+            tag::UINT32 => Ok(CloneValue::Number(self.read_varint()? as f64)),
+            tag::DOUBLE => Ok(CloneValue::Number(self.read_double()?)),
+            tag::BIGINT => self.read_bigint_contents(),
+
+            tag::UTF8_STRING | tag::ONE_BYTE_STRING | tag::TWO_BYTE_STRING => {
+                Ok(CloneValue::String(self.read_string_contents(tag)?))
+            }
+
+            tag::OBJECT_REFERENCE => Ok(CloneValue::Reference(self.read_varint()?)),
+
+            tag::BEGIN_JS_OBJECT => {
+                let id = self.next_id();
+                let properties = self.read_properties(tag::END_JS_OBJECT)?;
+                self.read_varint()?;
+
+                Ok(CloneValue::Object { id, properties })
+            }
+
+            tag::BEGIN_SPARSE_JS_ARRAY => {
+                let id = self.next_id();
+                let length = self.read_varint()?;
+                let properties = self.read_properties(tag::END_SPARSE_JS_ARRAY)?;
+                self.read_varint()?;
+                self.read_varint()?;
+
+                Ok(CloneValue::SparseArray {
+                    id,
+                    length,
+                    properties,
+                })
+            }
+
+            tag::BEGIN_DENSE_JS_ARRAY => {
+                let id = self.next_id();
+                let length = self.read_varint()? as usize;
+
+                let mut elements = Vec::with_capacity(length.min(self.bytes.len()));
+
+                for _ in 0..length {
+                    elements.push(self.read_value()?);
+                }
+
+                let properties = self.read_properties(tag::END_DENSE_JS_ARRAY)?;
+                self.read_varint()?;
+                self.read_varint()?;
+
+                Ok(CloneValue::Array {
+                    id,
+                    elements,
+                    properties,
+                })
+            }
+
+            tag::DATE => {
+                let id = self.next_id();
+
+                Ok(CloneValue::Date {
+                    id,
+                    epoch_milliseconds: self.read_double()?,
+                })
+            }
+
+            tag::TRUE_OBJECT | tag::FALSE_OBJECT => Ok(CloneValue::BooleanObject {
+                id: self.next_id(),
+                value: tag == tag::TRUE_OBJECT,
+            }),
+
+            tag::NUMBER_OBJECT => {
+                let id = self.next_id();
+
+                Ok(CloneValue::NumberObject {
+                    id,
+                    value: self.read_double()?,
+                })
+            }
+
+            tag::BIGINT_OBJECT => {
+                let id = self.next_id();
+
+                Ok(CloneValue::BigIntObject {
+                    id,
+                    value: Box::new(self.read_bigint_contents()?),
+                })
+            }
+
+            tag::STRING_OBJECT => {
+                let id = self.next_id();
+
+                Ok(CloneValue::StringObject {
+                    id,
+                    value: self.read_string()?,
+                })
+            }
+
+            tag::REGEXP => {
+                let id = self.next_id();
+                let pattern = self.read_string()?;
+                let flags = regexp_flags(self.read_varint()?);
+
+                Ok(CloneValue::RegExp { id, pattern, flags })
+            }
+
+            tag::BEGIN_JS_MAP => {
+                let id = self.next_id();
+                let mut entries = Vec::new();
+
+                while self.peek_tag()? != tag::END_JS_MAP {
+                    let key = self.read_value()?;
+                    let value = self.read_value()?;
+
+                    entries.push((key, value));
+                }
+
+                self.read_tag()?;
+                self.read_varint()?;
+
+                Ok(CloneValue::Map { id, entries })
+            }
+
+            tag::BEGIN_JS_SET => {
+                let id = self.next_id();
+                let mut values = Vec::new();
+
+                while self.peek_tag()? != tag::END_JS_SET {
+                    values.push(self.read_value()?);
+                }
+
+                self.read_tag()?;
+                self.read_varint()?;
+
+                Ok(CloneValue::Set { id, values })
+            }
+
+            tag::ARRAY_BUFFER => {
+                let id = self.next_id();
+                self.array_buffer_ids.insert(id);
+                let byte_length = self.read_varint()? as usize;
+
+                Ok(CloneValue::ArrayBuffer {
+                    id,
+                    bytes: self.read_bytes(byte_length)?.to_vec(),
+                })
+            }
+
+            tag::RESIZABLE_ARRAY_BUFFER => {
+                let id = self.next_id();
+                self.array_buffer_ids.insert(id);
+                let byte_length = self.read_varint()? as usize;
+                let _max_byte_length = self.read_varint()?;
+
+                Ok(CloneValue::ArrayBuffer {
+                    id,
+                    bytes: self.read_bytes(byte_length)?.to_vec(),
+                })
+            }
+
+            tag::ERROR => self.read_error(),
+
+            _ => Err(format!(
+                "Unsupported structured clone tag '{}' at offset {}.",
+                tag as char,
+                self.position - 1
+            )),
+        }
+    }
+
+    fn read_properties(&mut self, end_tag: u8) -> Result<Vec<(String, CloneValue)>, String> {
+        let mut properties = Vec::new();
+
+        loop {
+            if self.peek_tag()? == end_tag {
+                self.read_tag()?;
+
+                return Ok(properties);
+            }
+
+            let key = match self.read_value()? {
+                CloneValue::String(s) => s,
+                CloneValue::Number(n) => format_number_key(n),
+                other => return Err(format!("Unexpected property key {:?}.", other)),
+            };
+
+            let value = self.read_value()?;
+
+            properties.push((key, value));
+        }
+    }
+
+    fn read_array_buffer_view(&mut self, buffer: CloneValue) -> Result<CloneValue, String> {
+        let id = self.next_id();
+
+        let kind = match self.read_byte()? {
+            b'b' => ArrayBufferViewKind::Int8Array,
+            b'B' => ArrayBufferViewKind::Uint8Array,
+            b'C' => ArrayBufferViewKind::Uint8ClampedArray,
+            b'w' => ArrayBufferViewKind::Int16Array,
+            b'W' => ArrayBufferViewKind::Uint16Array,
+            b'd' => ArrayBufferViewKind::Int32Array,
+            b'D' => ArrayBufferViewKind::Uint32Array,
+            b'f' => ArrayBufferViewKind::Float32Array,
+            b'F' => ArrayBufferViewKind::Float64Array,
+            b'q' => ArrayBufferViewKind::BigInt64Array,
+            b'Q' => ArrayBufferViewKind::BigUint64Array,
+            b'?' => ArrayBufferViewKind::DataView,
+            other => {
+                return Err(format!(
+                    "Unsupported array buffer view tag '{}'.",
+                    other as char
+                ))
+            }
+        };
+
+        let byte_offset = self.read_varint()?;
+        let byte_length = self.read_varint()?;
+
+        if self.version >= 14 {
+            let _flags = self.read_varint()?;
+        }
+
+        Ok(CloneValue::ArrayBufferView {
+            id,
+            kind,
+            buffer: Box::new(buffer),
+            byte_offset,
+            byte_length,
+        })
+    }
+
+    fn read_error(&mut self) -> Result<CloneValue, String> {
+        let id = self.next_id();
+
+        let mut name = String::from("Error");
+        let mut message = None;
+        let mut stack = None;
+        let mut cause = None;
+
+        loop {
+            match self.read_varint()? as u8 {
+                error_tag::EVAL_ERROR_PROTOTYPE => name = String::from("EvalError"),
+                error_tag::RANGE_ERROR_PROTOTYPE => name = String::from("RangeError"),
+                error_tag::REFERENCE_ERROR_PROTOTYPE => name = String::from("ReferenceError"),
+                error_tag::SYNTAX_ERROR_PROTOTYPE => name = String::from("SyntaxError"),
+                error_tag::TYPE_ERROR_PROTOTYPE => name = String::from("TypeError"),
+                error_tag::URI_ERROR_PROTOTYPE => name = String::from("URIError"),
+                error_tag::MESSAGE => message = Some(self.read_string()?),
+                error_tag::STACK => stack = Some(self.read_string()?),
+                error_tag::CAUSE => cause = Some(Box::new(self.read_value()?)),
+                error_tag::END => break,
+                other => return Err(format!("Unsupported error tag '{}'.", other as char)),
+            }
+        }
+
+        Ok(CloneValue::Error {
+            id,
+            name,
+            message,
+            stack,
+            cause,
+        })
+    }
+}
+
+fn format_number_key(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e21 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+fn regexp_flags(bits: u32) -> String {
+    // Bit positions follow JSRegExp::Flags, letters are emitted in the same order as `RegExp.prototype.flags`.
+    const FLAGS: [(u32, char); 9] = [
+        (1 << 7, 'd'),
+        (1 << 0, 'g'),
+        (1 << 1, 'i'),
+        (1 << 6, 'l'),
+        (1 << 2, 'm'),
+        (1 << 5, 's'),
+        (1 << 4, 'u'),
+        (1 << 8, 'v'),
+        (1 << 3, 'y'),
+    ];
+
+    FLAGS
+        .iter()
+        .filter(|(bit, _)| bits & bit != 0)
+        .map(|(_, letter)| *letter)
+        .collect()
+}
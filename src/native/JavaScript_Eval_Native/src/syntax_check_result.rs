@@ -0,0 +1,89 @@
+use std::{ffi::CString, os::raw::c_char, ptr};
+
+use crate::{primitive_result::into_c_string, v8facade::SyntaxDiagnostic};
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct UnsafeSyntaxDiagnostic {
+    pub message: *mut c_char,
+    pub line: usize,
+    pub column: usize,
+    pub start_position: usize,
+    pub end_position: usize,
+    pub source_line: *mut c_char,
+}
+
+impl UnsafeSyntaxDiagnostic {
+    fn from_diagnostic(diagnostic: SyntaxDiagnostic) -> UnsafeSyntaxDiagnostic {
+        UnsafeSyntaxDiagnostic {
+            message: into_c_string(diagnostic.message).into_raw(),
+            line: diagnostic.line,
+            column: diagnostic.column,
+            start_position: diagnostic.start_position,
+            end_position: diagnostic.end_position,
+            source_line: into_c_string(diagnostic.source_line).into_raw(),
+        }
+    }
+}
+
+// An empty list of diagnostics means the source compiled. `error` is only set when the check itself
+// couldn't be carried out.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SyntaxCheckResult {
+    pub diagnostics: *mut UnsafeSyntaxDiagnostic,
+    pub diagnostics_length: usize,
+
+    pub error: *mut c_char,
+}
+
+impl SyntaxCheckResult {
+    pub fn from_result(result: Result<Vec<SyntaxDiagnostic>, String>) -> SyntaxCheckResult {
+        match result {
+            Ok(diagnostics) => {
+                let diagnostics: Box<[UnsafeSyntaxDiagnostic]> = diagnostics
+                    .into_iter()
+                    .map(UnsafeSyntaxDiagnostic::from_diagnostic)
+                    .collect();
+
+                let diagnostics_length = diagnostics.len();
+
+                SyntaxCheckResult {
+                    diagnostics: Box::into_raw(diagnostics) as *mut UnsafeSyntaxDiagnostic,
+                    diagnostics_length,
+                    error: ptr::null_mut(),
+                }
+            }
+
+            Err(error) => SyntaxCheckResult {
+                diagnostics: ptr::null_mut(),
+                diagnostics_length: 0,
+                error: into_c_string(error).into_raw(),
+            },
+        }
+    }
+
+    pub fn into_raw(self) -> *mut SyntaxCheckResult {
+        Box::into_raw(Box::new(self))
+    }
+
+    pub unsafe fn free_raw(raw_syntax_check_result: *mut SyntaxCheckResult) {
+        let result = Box::from_raw(raw_syntax_check_result);
+
+        if !result.diagnostics.is_null() {
+            let diagnostics = Box::from_raw(ptr::slice_from_raw_parts_mut(
+                result.diagnostics,
+                result.diagnostics_length,
+            ));
+
+            for diagnostic in diagnostics.iter() {
+                drop(CString::from_raw(diagnostic.message));
+                drop(CString::from_raw(diagnostic.source_line));
+            }
+        }
+
+        if !result.error.is_null() {
+            drop(CString::from_raw(result.error));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::ptr;
+
+use crate::{
+    primitive_result::{ByteBuffer, UnsafeJavaScriptError},
+    v8facade::{JavaScriptResult, Output},
+};
+
+// Bumped whenever the layout of `TaggedPrimitive` changes. Hosts set it on every parameter they pass
+// and check it on every result they receive.
+pub const TAGGED_PRIMITIVE_VERSION: u32 = 1;
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimitiveKind {
+    Null = 0,
+    Undefined = 1,
+    // UTF-8 bytes in `buffer`, may contain NULs.
+    String = 2,
+    // The description as UTF-8 bytes in `buffer`, `data` is null when the symbol has none.
+    Symbol = 3,
+    Number = 4,
+    BigInt = 5,
+    Bool = 6,
+    // JSON text in `buffer`.
+    Array = 7,
+    Object = 8,
+    Binary = 9,
+    // Reserved for handles to values kept alive in the isolate. They aren't implemented, a parameter of
+    // this kind is refused with an error and no result has it.
+    Handle = 10,
+    // Results only, see `error`.
+    Error = 11,
+    // Results only, V8 `ValueSerializer` bytes in `buffer`.
+    Serialized = 12,
+    // UTF-16LE code units in `buffer`, `length` is in bytes.
+    Utf16String = 13,
+    // Results only, see `function`.
+    Function = 14,
+    // Results only, milliseconds since the epoch in `number`.
+    Date = 15,
+    // Results only, see `regexp`.
+    RegExp = 16,
+}
+
+impl PrimitiveKind {
+    pub fn from_u32(kind: u32) -> Option<PrimitiveKind> {
+        let kind = match kind {
+            0 => PrimitiveKind::Null,
+            1 => PrimitiveKind::Undefined,
+            2 => PrimitiveKind::String,
+            3 => PrimitiveKind::Symbol,
+            4 => PrimitiveKind::Number,
+            5 => PrimitiveKind::BigInt,
+            6 => PrimitiveKind::Bool,
+            7 => PrimitiveKind::Array,
+            8 => PrimitiveKind::Object,
+            9 => PrimitiveKind::Binary,
+            10 => PrimitiveKind::Handle,
+            11 => PrimitiveKind::Error,
+            12 => PrimitiveKind::Serialized,
+            13 => PrimitiveKind::Utf16String,
+            14 => PrimitiveKind::Function,
+            15 => PrimitiveKind::Date,
+            16 => PrimitiveKind::RegExp,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union TaggedValue {
+    pub number: f64,
+    pub bigint: i64,
+    // Anything but 0 is true.
+    pub boolean: u8,
+    pub buffer: ByteBuffer,
+    pub error: *mut UnsafeJavaScriptError,
+    pub function: *mut UnsafeFunction,
+    pub regexp: *mut UnsafeRegExp,
+}
+
+// Strings are UTF-8 bytes.
+#[repr(C)]
+pub struct UnsafeFunction {
+    pub name: ByteBuffer,
+    pub arity: u32,
+    pub source: ByteBuffer,
+}
+
+#[repr(C)]
+pub struct UnsafeRegExp {
+    pub pattern: ByteBuffer,
+    pub flags: ByteBuffer,
+}
+
+// `kind` holds a `PrimitiveKind` and decides which field of `value` is read. When the host passes these
+// as parameters it owns every buffer, the bytes are copied before the call returns. Results are owned by
+// Rust and must be handed back to `free_tagged_primitive`.
+#[repr(C)]
+pub struct TaggedPrimitive {
+    pub version: u32,
+    pub kind: u32,
+    pub value: TaggedValue,
+}
+
+impl TaggedPrimitive {
+    fn new(kind: PrimitiveKind, value: TaggedValue) -> TaggedPrimitive {
+        TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: kind as u32,
+            value,
+        }
+    }
+
+    fn with_buffer(kind: PrimitiveKind, bytes: Vec<u8>) -> TaggedPrimitive {
+        TaggedPrimitive::new(
+            kind,
+            TaggedValue {
+                buffer: ByteBuffer::from_vec(bytes),
+            },
+        )
+    }
+
+    pub fn from_output(output: Output) -> TaggedPrimitive {
+        match output {
+            Output::Result(r) => TaggedPrimitive::from_javascriptresult(r),
+
+            Output::Error(e) => TaggedPrimitive::new(
+                PrimitiveKind::Error,
+                TaggedValue {
+                    error: UnsafeJavaScriptError::from_error(e).into_raw(),
+                },
+            ),
+
+            // You can't get heap statistics or syntax diagnostics out of V8 by invoking script so these
+            // results are impossible.
+            Output::HeapStatistics(_) | Output::SyntaxDiagnostics(_) => unreachable!(),
+        }
+    }
+
+    pub fn from_javascriptresult(result: JavaScriptResult) -> TaggedPrimitive {
+        match result {
+            JavaScriptResult::NullValue => {
+                TaggedPrimitive::new(PrimitiveKind::Null, TaggedValue { number: 0.0 })
+            }
+            JavaScriptResult::UndefinedValue => {
+                TaggedPrimitive::new(PrimitiveKind::Undefined, TaggedValue { number: 0.0 })
+            }
+            JavaScriptResult::StringValue(v) => {
+                TaggedPrimitive::with_buffer(PrimitiveKind::String, v.into_bytes())
+            }
+            JavaScriptResult::Utf16StringValue(v) => TaggedPrimitive::with_buffer(
+                PrimitiveKind::Utf16String,
+                v.iter().flat_map(|u| u.to_le_bytes().to_vec()).collect(),
+            ),
+            JavaScriptResult::NumberValue(v) => {
+                TaggedPrimitive::new(PrimitiveKind::Number, TaggedValue { number: v })
+            }
+            JavaScriptResult::BigIntValue(v) => {
+                TaggedPrimitive::new(PrimitiveKind::BigInt, TaggedValue { bigint: v })
+            }
+            JavaScriptResult::BoolValue(v) => {
+                TaggedPrimitive::new(PrimitiveKind::Bool, TaggedValue { boolean: v as u8 })
+            }
+            JavaScriptResult::ArrayValue(v) => {
+                TaggedPrimitive::with_buffer(PrimitiveKind::Array, v.into_bytes())
+            }
+            JavaScriptResult::ObjectValue(v) => {
+                TaggedPrimitive::with_buffer(PrimitiveKind::Object, v.into_bytes())
+            }
+            JavaScriptResult::SerializedValue(v) => {
+                TaggedPrimitive::with_buffer(PrimitiveKind::Serialized, v)
+            }
+            JavaScriptResult::BinaryValue { bytes, .. } => {
+                TaggedPrimitive::with_buffer(PrimitiveKind::Binary, bytes)
+            }
+            JavaScriptResult::SymbolValue(Some(v)) => {
+                TaggedPrimitive::with_buffer(PrimitiveKind::Symbol, v.into_bytes())
+            }
+            JavaScriptResult::SymbolValue(None) => TaggedPrimitive::new(
+                PrimitiveKind::Symbol,
+                TaggedValue {
+                    buffer: ByteBuffer {
+                        data: ptr::null_mut(),
+                        length: 0,
+                    },
+                },
+            ),
+            JavaScriptResult::FunctionValue {
+                name,
+                arity,
+                source,
+            } => TaggedPrimitive::new(
+                PrimitiveKind::Function,
+                TaggedValue {
+                    function: Box::into_raw(Box::new(UnsafeFunction {
+                        name: ByteBuffer::from_vec(name.into_bytes()),
+                        arity,
+                        source: ByteBuffer::from_vec(source.into_bytes()),
+                    })),
+                },
+            ),
+            JavaScriptResult::DateValue {
+                epoch_milliseconds, ..
+            } => TaggedPrimitive::new(
+                PrimitiveKind::Date,
+                TaggedValue {
+                    number: epoch_milliseconds,
+                },
+            ),
+            JavaScriptResult::RegExpValue { pattern, flags, .. } => TaggedPrimitive::new(
+                PrimitiveKind::RegExp,
+                TaggedValue {
+                    regexp: Box::into_raw(Box::new(UnsafeRegExp {
+                        pattern: ByteBuffer::from_vec(pattern.into_bytes()),
+                        flags: ByteBuffer::from_vec(flags.into_bytes()),
+                    })),
+                },
+            ),
+        }
+    }
+
+    pub fn into_raw(self) -> *mut TaggedPrimitive {
+        Box::into_raw(Box::new(self))
+    }
+
+    pub unsafe fn free_raw(raw_tagged_primitive: *mut TaggedPrimitive) {
+        let tagged_primitive = Box::from_raw(raw_tagged_primitive);
+
+        match PrimitiveKind::from_u32(tagged_primitive.kind) {
+            Some(PrimitiveKind::String)
+            | Some(PrimitiveKind::Utf16String)
+            | Some(PrimitiveKind::Symbol)
+            | Some(PrimitiveKind::Array)
+            | Some(PrimitiveKind::Object)
+            | Some(PrimitiveKind::Binary)
+            | Some(PrimitiveKind::Serialized) => tagged_primitive.value.buffer.free_data(),
+
+            Some(PrimitiveKind::Error) => {
+                if !tagged_primitive.value.error.is_null() {
+                    UnsafeJavaScriptError::free_raw(tagged_primitive.value.error);
+                }
+            }
+
+            Some(PrimitiveKind::Function) => {
+                if !tagged_primitive.value.function.is_null() {
+                    let function = Box::from_raw(tagged_primitive.value.function);
+
+                    function.name.free_data();
+                    function.source.free_data();
+                }
+            }
+
+            Some(PrimitiveKind::RegExp) => {
+                if !tagged_primitive.value.regexp.is_null() {
+                    let regexp = Box::from_raw(tagged_primitive.value.regexp);
+
+                    regexp.pattern.free_data();
+                    regexp.flags.free_data();
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+use crate::{deterministic, v8facade::JavaScriptError};
+
+// Pending timers live in an isolate slot. They're run by the worker loop in `V8Facade::new` once they're
+// due, before it handles the next input or waits for one, and by `V8Facade::run_until_idle` and
+// `V8Facade::run_with_await`.
+//
+// Times are in milliseconds. In a deterministic context they're read from the host's clock, so timers
+// only come due when the host moves it with `V8Facade::set_time`, and waiting never makes them due.
+// Otherwise they're the milliseconds since the timers were installed.
+struct Timer {
+    due: f64,
+    interval: Option<f64>,
+    callback: v8::Global<v8::Function>,
+    arguments: Vec<v8::Global<v8::Value>>,
+}
+
+struct Timers {
+    origin: Instant,
+    next_id: u32,
+    pending: BTreeMap<u32, Timer>,
+}
+
+impl Timers {
+    fn insert(&mut self, timer: Timer) -> u32 {
+        // Ids start at 1 so they're always truthy, the same as in browsers.
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.pending.insert(self.next_id, timer);
+
+        self.next_id
+    }
+
+    fn next_due(&self) -> Option<(u32, f64)> {
+        self.pending
+            .iter()
+            .map(|(id, timer)| (*id, timer.due))
+            .min_by(|(a_id, a_due), (b_id, b_due)| a_due.total_cmp(b_due).then(a_id.cmp(b_id)))
+    }
+}
+
+// The longest delay browsers honour, anything above it fires immediately there too.
+const MAX_DELAY_MILLISECONDS: f64 = i32::MAX as f64;
+
+// A zero delay interval would keep the worker from ever waiting on its inputs.
+const MIN_INTERVAL_MILLISECONDS: f64 = 1.0;
+
+pub fn install(scope: &mut v8::HandleScope) -> Option<()> {
+    scope.set_slot(Timers {
+        origin: Instant::now(),
+        next_id: 0,
+        pending: BTreeMap::new(),
+    });
+
+    let global = scope.get_current_context().global(scope);
+
+    set_function(scope, global, "setTimeout", set_timeout)?;
+    set_function(scope, global, "setInterval", set_interval)?;
+    set_function(scope, global, "clearTimeout", clear_timer)?;
+    set_function(scope, global, "clearInterval", clear_timer)?;
+
+    Some(())
+}
+
+fn set_function(
+    scope: &mut v8::HandleScope,
+    global: v8::Local<v8::Object>,
+    name: &str,
+    callback: impl v8::MapFnTo<v8::FunctionCallback>,
+) -> Option<()> {
+    let name = v8::String::new(scope, name)?;
+    let function = v8::Function::new(scope, callback)?;
+
+    global.set(scope, name.into(), function.into())?;
+
+    Some(())
+}
+
+fn now(isolate: &v8::Isolate, timers: &Timers) -> f64 {
+    deterministic::time(isolate).unwrap_or_else(|| timers.origin.elapsed().as_secs_f64() * 1000.0)
+}
+
+// When the next timer comes due on the real clock. In a deterministic context that's now for a timer
+// that's already due and never for the rest.
+pub fn next_due(isolate: &v8::Isolate) -> Option<Instant> {
+    let timers = isolate.get_slot::<Timers>()?;
+    let (_, due) = timers.next_due()?;
+
+    let now = now(isolate, timers);
+
+    if due <= now {
+        Some(Instant::now())
+    } else if deterministic::time(isolate).is_some() {
+        None
+    } else {
+        Some(Instant::now() + Duration::from_secs_f64((due - now) / 1000.0))
+    }
+}
+
+pub fn clear(isolate: &mut v8::Isolate) {
+    if let Some(timers) = isolate.get_slot_mut::<Timers>() {
+        timers.pending.clear();
+    }
+}
+
+// Runs every timer that is due, in the order they came due, with a microtask checkpoint after each one
+// like browsers do. Stops at the first callback that throws and hands back what it threw.
+pub fn run_due(scope: &mut v8::HandleScope) -> Option<JavaScriptError> {
+    let now = now(scope, scope.get_slot::<Timers>()?);
+
+    loop {
+        let (id, timer) = {
+            let timers = scope.get_slot_mut::<Timers>()?;
+
+            match timers.next_due() {
+                Some((id, due)) if due <= now => (id, timers.pending.remove(&id)?),
+                _ => return None,
+            }
+        };
+
+        let scope = &mut v8::HandleScope::new(scope);
+
+        let callback = v8::Local::new(scope, &timer.callback);
+        let arguments: Vec<v8::Local<v8::Value>> = timer
+            .arguments
+            .iter()
+            .map(|argument| v8::Local::new(scope, argument))
+            .collect();
+
+        // Rescheduled before the callback runs so it can clear its own interval.
+        if let Some(interval) = timer.interval {
+            if let Some(timers) = scope.get_slot_mut::<Timers>() {
+                timers.pending.insert(
+                    id,
+                    Timer {
+                        due: now + interval,
+                        ..timer
+                    },
+                );
+            }
+        }
+
+        let tc = &mut v8::TryCatch::new(scope);
+
+        let receiver = tc.get_current_context().global(tc);
+        let result = callback.call(tc, receiver.into(), &arguments);
+
+        tc.perform_microtask_checkpoint();
+
+        if result.is_none() {
+            return Some(JavaScriptError::from_exception(tc));
+        }
+    }
+}
+
+fn set_timeout(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    rv: v8::ReturnValue,
+) {
+    schedule(scope, args, rv, false);
+}
+
+fn set_interval(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    rv: v8::ReturnValue,
+) {
+    schedule(scope, args, rv, true);
+}
+
+fn schedule(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+    repeat: bool,
+) {
+    let callback = match v8::Local::<v8::Function>::try_from(args.get(0)) {
+        Ok(callback) => callback,
+        Err(_) => {
+            let message = "The timer callback must be a function.";
+
+            if let Some(message) = v8::String::new(scope, message) {
+                let exception = v8::Exception::type_error(scope, message);
+
+                scope.throw_exception(exception);
+            }
+            return;
+        }
+    };
+
+    // `None` means converting the delay threw, that exception is already on its way to the caller.
+    let delay = match args.get(1).number_value(scope) {
+        Some(delay) if delay > 0.0 && delay <= MAX_DELAY_MILLISECONDS => delay,
+        Some(_) => 0.0,
+        None => return,
+    };
+
+    let interval = if repeat {
+        Some(delay.max(MIN_INTERVAL_MILLISECONDS))
+    } else {
+        None
+    };
+
+    let now = match scope.get_slot::<Timers>() {
+        Some(timers) => now(scope, timers),
+        None => return,
+    };
+
+    let arguments = (2..args.length())
+        .map(|i| v8::Global::new(scope, args.get(i)))
+        .collect();
+
+    let timer = Timer {
+        due: now + delay,
+        interval,
+        callback: v8::Global::new(scope, callback),
+        arguments,
+    };
+
+    if let Some(timers) = scope.get_slot_mut::<Timers>() {
+        let id = timers.insert(timer);
+
+        rv.set(v8::Integer::new_from_unsigned(scope, id).into());
+    }
+}
+
+fn clear_timer(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _rv: v8::ReturnValue,
+) {
+    if let Ok(id) = v8::Local::<v8::Uint32>::try_from(args.get(0)) {
+        if let Some(timers) = scope.get_slot_mut::<Timers>() {
+            timers.pending.remove(&id.value());
+        }
+    }
+}
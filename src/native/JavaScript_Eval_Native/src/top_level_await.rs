@@ -0,0 +1,496 @@
+// Classic scripts can't `await` at the top level, so the source is run as the body of an async arrow
+// function instead. To hand back the value of the last expression the way `run` does, the source is
+// split where its final statement begins and that statement is returned from the function.
+//
+// Finding that statement only needs the top-level statement boundaries: `;`, a `}` closing a block
+// and newlines, skipping over strings, templates, comments and regular expressions. Only splits after
+// the last `;` or `}` are candidates, each is checked by compiling the wrapped source, starting with
+// the longest possible final statement so that a newline is only treated as a boundary where automatic
+// semicolon insertion would make it one. When none of them compile, say because the final statement
+// isn't an expression, the source runs as is and the result is `undefined`.
+//
+// In the function body `var` and function declarations would be local, so the ones at the top level
+// are made globals the way a classic script would. `var a = await f(), b` becomes the assignment
+// `void (a = await f(), b)` and the same statement is repeated ahead of the function, in a branch
+// that never runs, so that V8 declares every name it binds, destructured ones included. A declared
+// function is assigned to the global of the same name before the statement that declares it. `let`,
+// `const` and `class` declarations, and `var` statements nested in other statements, stay local to
+// the run.
+
+struct Boundary {
+    // Where the next statement may begin.
+    position: usize,
+
+    // `;` or `}` rather than a newline, nothing before one of these can be part of the final statement.
+    hard: bool,
+
+    // The last significant byte before the boundary. After an operator or an opening bracket a newline
+    // can't end the statement.
+    previous: u8,
+}
+
+struct Scan {
+    boundaries: Vec<Boundary>,
+
+    // Where each `await` starts.
+    awaits: Vec<usize>,
+}
+
+pub fn wrap(scope: &mut v8::HandleScope, source: &str) -> String {
+    let (declarations, body) = hoist_declarations(scope, source);
+
+    format!("{}{}", declarations, wrap_body(scope, &body))
+}
+
+fn wrap_body(scope: &mut v8::HandleScope, source: &str) -> String {
+    let mut boundaries = scan(source).boundaries;
+    let mut end = source.len();
+
+    // Drop trailing semicolons, whitespace and comments.
+    while let Some(last) = boundaries.last() {
+        if !is_blank(&source[last.position..end]) {
+            break;
+        }
+
+        if source[..last.position].ends_with(';') {
+            end = last.position - 1;
+        }
+
+        boundaries.pop();
+    }
+
+    // Going back past the last `;` or `}` would mean compiling the whole source once per boundary.
+    let first = boundaries.iter().rposition(|b| b.hard).unwrap_or(0);
+
+    for boundary in &boundaries[first..] {
+        let statement = &source[boundary.position..end];
+
+        // An expression statement can't start with `function` or `class`, returning one would turn a
+        // declaration into an expression.
+        if is_blank(statement) || declared_function_name(statement).is_some() {
+            continue;
+        }
+
+        let rest = &source.as_bytes()[skip_blank(source.as_bytes(), boundary.position)..end];
+
+        if starts_with_keyword(rest, b"class") {
+            continue;
+        }
+
+        let wrapped = wrap_returning(&source[..boundary.position], statement);
+
+        if compiles(scope, &wrapped) {
+            return wrapped;
+        }
+    }
+
+    format!("(async () => {{{}\n}})()", source)
+}
+
+fn wrap_returning(statements: &str, expression: &str) -> String {
+    format!(
+        "(async () => {{{}return ({}\n);\n}})()",
+        statements, expression
+    )
+}
+
+// Returns the declarations to run ahead of the wrapper and the source with its `var` statements turned
+// into assignments. When the rewritten source doesn't compile it's returned as it was.
+fn hoist_declarations(scope: &mut v8::HandleScope, source: &str) -> (String, String) {
+    let bytes = source.as_bytes();
+    let scan = scan(source);
+
+    let mut declarations = String::new();
+    let mut body = String::new();
+    let mut copied = 0;
+
+    for (i, boundary) in scan.boundaries.iter().enumerate() {
+        let start = skip_blank(bytes, boundary.position);
+
+        // Inside a `var` statement that was already rewritten.
+        if start < copied {
+            continue;
+        }
+
+        if starts_with_keyword(&bytes[start..], b"var") {
+            let bindings = start + 3;
+
+            if let Some(end) = var_statement_end(scope, source, bindings, &scan.boundaries[i + 1..])
+            {
+                declarations.push_str(&format!(
+                    "if (false) {{ var {}\n}}\n",
+                    without_awaits(source, bindings, end, &scan.awaits)
+                ));
+
+                body.push_str(&source[copied..start]);
+                body.push_str(&format!("void ({}\n)", &source[bindings..end]));
+                copied = end;
+            }
+        } else if boundary.hard || !starts_regex(boundary.previous) {
+            if let Some(name) = declared_function_name(&source[start..]) {
+                declarations.push_str(&format!("var {};\n", name));
+
+                body.push_str(&source[copied..start]);
+                body.push_str(&format!(";this.{0} = {0};\n", name));
+                copied = start;
+            }
+        }
+    }
+
+    if declarations.is_empty() {
+        return (declarations, String::from(source));
+    }
+
+    body.push_str(&source[copied..]);
+
+    if compiles(scope, &declarations) && compiles(scope, &format!("(async () => {{{}\n}})", body)) {
+        (declarations, body)
+    } else {
+        (String::new(), String::from(source))
+    }
+}
+
+// The end of the bindings of the `var` statement starting at `start`, without its `;`. A statement ends
+// at the first `;` outside of brackets at the latest, the boundaries before it are tried longest first
+// for the same reason as for the final statement.
+fn var_statement_end(
+    scope: &mut v8::HandleScope,
+    source: &str,
+    start: usize,
+    boundaries: &[Boundary],
+) -> Option<usize> {
+    let last = boundaries
+        .iter()
+        .position(|b| source[..b.position].ends_with(';'))
+        .map_or(boundaries.len(), |i| i + 1);
+
+    let mut ends: Vec<usize> = boundaries[..last]
+        .iter()
+        .map(|b| b.position - source[..b.position].ends_with(';') as usize)
+        .collect();
+
+    if last == boundaries.len() {
+        ends.push(source.len());
+    }
+
+    ends.into_iter().rev().find(|end| {
+        compiles(
+            scope,
+            &format!("(async () => {{ void ({}\n) }})", &source[start..*end]),
+        )
+    })
+}
+
+// Only the branch that never runs sees these, so `await` can simply be dropped to keep the bindings
+// valid outside of an async function.
+fn without_awaits(source: &str, start: usize, end: usize, awaits: &[usize]) -> String {
+    let mut result = String::from(&source[start..end]);
+
+    for position in awaits.iter().filter(|p| **p >= start && **p < end) {
+        result.replace_range(position - start..position - start + 5, "     ");
+    }
+
+    result
+}
+
+// `function name`, `function* name` and their `async` forms.
+fn declared_function_name(statement: &str) -> Option<&str> {
+    let bytes = statement.as_bytes();
+    let mut i = skip_blank(bytes, 0);
+
+    if starts_with_keyword(&bytes[i..], b"async") {
+        i += 5;
+
+        // A line break after `async` ends the statement.
+        while i < bytes.len() && (bytes[i] == b' ' || bytes[i] == b'\t') {
+            i += 1;
+        }
+    }
+
+    if !starts_with_keyword(&bytes[i..], b"function") {
+        return None;
+    }
+
+    i = skip_blank(bytes, i + 8);
+
+    if bytes.get(i) == Some(&b'*') {
+        i = skip_blank(bytes, i + 1);
+    }
+
+    let name_length = bytes[i..]
+        .iter()
+        .take_while(|b| is_identifier_byte(**b))
+        .count();
+
+    if name_length == 0 {
+        None
+    } else {
+        Some(&statement[i..i + name_length])
+    }
+}
+
+fn starts_with_keyword(bytes: &[u8], keyword: &[u8]) -> bool {
+    bytes.starts_with(keyword)
+        && !bytes
+            .get(keyword.len())
+            .map_or(false, |b| is_identifier_byte(*b))
+}
+
+fn is_identifier_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+}
+
+fn compiles(scope: &mut v8::HandleScope, source: &str) -> bool {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    match v8::String::new(tc, source) {
+        Some(source) => v8::Script::compile(tc, source, None).is_some(),
+        None => false,
+    }
+}
+
+fn scan(source: &str) -> Scan {
+    let bytes = source.as_bytes();
+
+    let mut boundaries = vec![Boundary {
+        position: 0,
+        hard: true,
+        previous: b';',
+    }];
+    let mut awaits = Vec::new();
+
+    // Open brackets, with a backtick standing in for a template substitution.
+    let mut open: Vec<u8> = Vec::new();
+
+    // The last significant byte, it decides whether a `/` starts a regular expression.
+    let mut previous = b';';
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        match b {
+            b'\'' | b'"' => {
+                i = skip_string(bytes, i);
+                previous = b'"';
+                continue;
+            }
+
+            b'`' => {
+                i = skip_template(bytes, i + 1, &mut open);
+                previous = b'"';
+                continue;
+            }
+
+            b'}' if open.last() == Some(&b'`') => {
+                open.pop();
+                i = skip_template(bytes, i + 1, &mut open);
+                previous = b'"';
+                continue;
+            }
+
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_block_comment(bytes, i);
+                continue;
+            }
+
+            b'/' if starts_regex(previous) => {
+                i = skip_regex(bytes, i);
+                previous = b'"';
+                continue;
+            }
+
+            b if is_identifier_byte(b) => {
+                let length = bytes[i..]
+                    .iter()
+                    .take_while(|b| is_identifier_byte(**b))
+                    .count();
+
+                if &bytes[i..i + length] == b"await" {
+                    awaits.push(i);
+                }
+
+                i += length;
+                previous = bytes[i - 1];
+                continue;
+            }
+
+            b'(' | b'[' | b'{' => open.push(b),
+
+            b')' | b']' => {
+                open.pop();
+            }
+
+            b'}' => {
+                open.pop();
+
+                if open.is_empty() {
+                    boundaries.push(Boundary {
+                        position: i + 1,
+                        hard: true,
+                        previous: b,
+                    });
+                }
+            }
+
+            b';' if open.is_empty() => boundaries.push(Boundary {
+                position: i + 1,
+                hard: true,
+                previous: b,
+            }),
+
+            b'\n' if open.is_empty() => boundaries.push(Boundary {
+                position: i + 1,
+                hard: false,
+                previous,
+            }),
+
+            _ => {}
+        }
+
+        if !b.is_ascii_whitespace() {
+            previous = b;
+        }
+
+        i += 1;
+    }
+
+    Scan { boundaries, awaits }
+}
+
+fn starts_regex(previous: u8) -> bool {
+    b"(,=:[!&|?{};+-*%<>~^".contains(&previous)
+}
+
+// Each of these returns the index just past what it skipped. Unterminated strings and regular
+// expressions stop at the end of the line, the compile check takes care of the rest.
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' => return i,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+// Stops after the closing backtick, or after `${` with a backtick pushed onto `open`.
+fn skip_template(bytes: &[u8], start: usize, open: &mut Vec<u8>) -> usize {
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => return i + 1,
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                open.push(b'`');
+                return i + 2;
+            }
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 2;
+
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'*' && bytes[i + 1] == b'/' {
+            return i + 2;
+        }
+
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+fn skip_regex(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    let mut in_class = false;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' => return i,
+            b'[' => {
+                in_class = true;
+                i += 1;
+            }
+            b']' => {
+                in_class = false;
+                i += 1;
+            }
+            b'/' if !in_class => {
+                i += 1;
+
+                while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+                    i += 1;
+                }
+
+                return i;
+            }
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+// Returns the index of the first byte from `start` on that isn't whitespace or part of a comment.
+fn skip_blank(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => return i,
+        }
+    }
+
+    bytes.len()
+}
+
+// Only whitespace, semicolons and comments.
+fn is_blank(source: &str) -> bool {
+    let bytes = source.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b';' => i += 1,
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => return false,
+        }
+    }
+
+    true
+}
@@ -1,28 +1,76 @@
-use std::{convert::TryFrom, sync::mpsc::RecvError, thread::JoinHandle};
+use std::{
+    convert::TryFrom,
+    path::PathBuf,
+    sync::mpsc::RecvError,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
 use std::sync::{mpsc, Once};
 
 use v8;
 
-use crate::{function_parameter::FunctionParameter, V8HeapStatistics};
+use crate::{
+    commonjs, deterministic, extended_json, function_parameter::FunctionParameter, globals,
+    intrinsics, modules, rejections, structured_clone, timers, top_level_await, wasm,
+    V8HeapStatistics,
+};
 
 static INIT_PLATFORM: Once = Once::new();
 
-fn init_platform() {
-    let platform = v8::new_default_platform(0, false).make_shared();
+// The same depth V8 uses for `Error.stack` by default.
+pub(crate) const STACK_TRACE_FRAME_LIMIT: i32 = 10;
+
+fn init_platform(flags: &str, thread_pool_size: u32) {
+    if !flags.is_empty() {
+        v8::V8::set_flags_from_string(flags);
+    }
+
+    let platform = v8::new_default_platform(thread_pool_size, false).make_shared();
     v8::V8::initialize_platform(platform);
     v8::V8::initialize();
 }
 
+// From src/code_generation.cc, the crate has no binding for it.
+extern "C" {
+    fn javascript_eval_native__Context__AllowCodeGenerationFromStrings(
+        context: *const v8::Context,
+        allow: bool,
+    );
+}
+
+// Makes `eval`, `new Function` and the like throw an `EvalError` in the context.
+fn disallow_code_generation(context: v8::Local<v8::Context>) {
+    unsafe { javascript_eval_native__Context__AllowCodeGenerationFromStrings(&*context, false) };
+}
+
 enum Input {
-    Source(String),
-    Function(FunctionCall),
+    Source(SourceText, Marshaling),
+    AwaitSource(String, Marshaling, Duration),
+    SourceUntilIdle(SourceText, Marshaling, Duration),
+    ModuleSource(String, String, Marshaling, Duration),
+    Function(FunctionCall, Marshaling),
+    CheckSyntax(SourceText, SourceKind),
     HeapReport,
 
-    BeginSource(String, Box<dyn FnOnce(Output) + Send>),
-    BeginFunction(FunctionCall, Box<dyn FnOnce(Output) + Send>),
+    BeginSource(SourceText, Marshaling, Box<dyn FnOnce(Output) + Send>),
+    BeginFunction(FunctionCall, Marshaling, Box<dyn FnOnce(Output) + Send>),
     BeginHeapReport(Box<dyn FnOnce(V8HeapStatistics) + Send>),
 
+    SetRejectionHandler(Option<Box<dyn FnMut(JavaScriptError) + Send>>),
+    SetModuleLoader(Option<modules::ModuleLoader>),
+    RegisterModule(String, String),
+    SetImportMap(String),
+    SetMicrotaskPolicy(MicrotaskPolicy),
+    MicrotaskCheckpoint,
+    EnqueueMicrotask(FunctionCall),
+    LoadWasm(String, Vec<u8>, Vec<WasmImport>),
+    WasmExports(String),
+    WasmFunction(String, FunctionCall),
+    Lockdown,
+    GlobalsReport,
+    SetTime(f64),
+    ClearTimers,
     Shutdown,
 }
 
@@ -30,6 +78,98 @@ pub enum Output {
     Result(JavaScriptResult),
     Error(JavaScriptError),
     HeapStatistics(V8HeapStatistics),
+    SyntaxDiagnostics(Vec<SyntaxDiagnostic>),
+}
+
+// How objects and arrays are marshaled back to the caller.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueEncoding {
+    Json,
+    // V8's `ValueSerializer` wire format, decode it with `structured_clone::decode`.
+    StructuredClone,
+    // JSON with tagged values for BigInts, non-finite numbers, dates and undefined, see
+    // `extended_json`. Object parameters are read in the same dialect.
+    ExtendedJson,
+}
+
+// How string results are handed back. UTF-8 can't represent unpaired surrogates, UTF-16 keeps
+// the string exactly as V8 holds it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StringEncoding {
+    Utf8,
+    Utf16,
+}
+
+#[derive(Clone, Copy)]
+struct Marshaling {
+    values: ValueEncoding,
+    strings: StringEncoding,
+}
+
+impl Marshaling {
+    fn new(values: ValueEncoding, strings: StringEncoding) -> Self {
+        Marshaling { values, strings }
+    }
+}
+
+impl Default for Marshaling {
+    fn default() -> Self {
+        Marshaling::new(ValueEncoding::Json, StringEncoding::Utf8)
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SourceKind {
+    Script,
+    Module,
+}
+
+// When promise continuations and other microtasks run. `Auto` runs them whenever a request's script
+// returns, `Explicit` leaves them queued until `perform_microtask_checkpoint`. Awaiting a result with
+// `run_with_await` or `run_until_idle`, and the timer loop, still run them either way.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MicrotaskPolicy {
+    Auto,
+    Explicit,
+}
+
+// Lines and columns are 1-based, the way they appear in stack traces. Positions are offsets into the
+// source in UTF-16 code units, `end_position` is exclusive.
+#[derive(Debug)]
+pub struct SyntaxDiagnostic {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub start_position: usize,
+    pub end_position: usize,
+    pub source_line: String,
+}
+
+impl SyntaxDiagnostic {
+    fn from_message(scope: &mut v8::HandleScope, message: v8::Local<v8::Message>) -> Self {
+        let source_line = match message.get_source_line(scope) {
+            Some(source_line) => source_line.to_rust_string_lossy(scope),
+            None => String::from(""),
+        };
+
+        SyntaxDiagnostic {
+            message: message.get(scope).to_rust_string_lossy(scope),
+            line: message.get_line_number(scope).unwrap_or(0),
+            column: message.get_start_column() + 1,
+            start_position: message.get_start_position().max(0) as usize,
+            end_position: message.get_end_position().max(0) as usize,
+            source_line,
+        }
+    }
+}
+
+pub enum SourceText {
+    Utf8(String),
+    Utf16(Vec<u16>),
 }
 
 pub struct FunctionCall {
@@ -37,7 +177,24 @@ pub struct FunctionCall {
     arguments: Vec<FunctionParameter>,
 }
 
+// A global the host defines when the context is created, its value is parsed from `json`.
+#[derive(Clone, Debug)]
+pub struct HostGlobal {
+    pub name: String,
+    pub json: String,
+}
+
+// Imports the value of the global `global` into a Wasm module as `module`.`name`.
+#[derive(Clone, Debug)]
+pub struct WasmImport {
+    pub module: String,
+    pub name: String,
+    pub global: String,
+}
+
 pub enum JavaScriptResult {
+    NullValue,
+    UndefinedValue,
     StringValue(String),
     NumberValue(f64),
     BigIntValue(i64),
@@ -46,47 +203,133 @@ pub enum JavaScriptResult {
     // These will be tossed back as JSON strings.
     ArrayValue(String),
     ObjectValue(String),
+
+    SerializedValue(Vec<u8>),
+
+    // The bytes of an ArrayBuffer, or of the range a typed array or DataView covers. `json` is what
+    // `JSON.stringify` made of the value before binary results existed, `None` when it throws, as it
+    // does for a BigInt64Array.
+    BinaryValue {
+        bytes: Vec<u8>,
+        json: Option<String>,
+    },
+
+    Utf16StringValue(Vec<u16>),
+
+    // `None` when the symbol was created without a description.
+    SymbolValue(Option<String>),
+
+    FunctionValue {
+        name: String,
+        arity: u32,
+        source: String,
+    },
+
+    // `epoch_milliseconds` is NaN for an invalid date. As with `BinaryValue`, `json` is what
+    // `JSON.stringify` made of these before they had variants of their own.
+    DateValue {
+        epoch_milliseconds: f64,
+        json: Option<String>,
+    },
+
+    RegExpValue {
+        pattern: String,
+        flags: String,
+        json: Option<String>,
+    },
 }
 
 impl JavaScriptResult {
-    pub fn from<'s>(
+    pub fn from(
         value: v8::Local<v8::Value>,
-        scope: &mut v8::HandleScope<'s>,
-        global: v8::Local<v8::Object>,
-    ) -> JavaScriptResult {
-        if value.is_string() {
-            let string_result = value.to_string(scope).unwrap();
-            JavaScriptResult::StringValue(string_result.to_rust_string_lossy(scope))
-        } else if value.is_number() {
-            let number_result = value.to_number(scope).unwrap();
-            JavaScriptResult::NumberValue(number_result.value())
-        } else if value.is_big_int() {
-            let bigint_result = value.to_big_int(scope).unwrap();
-            JavaScriptResult::BigIntValue(bigint_result.i64_value().0)
-        } else if value.is_boolean() {
-            let bool_result = value.to_boolean(scope);
-            JavaScriptResult::BoolValue(bool_result.boolean_value(scope))
-        } else {
-            let json = v8::String::new(scope, "JSON").unwrap();
-            let json = v8::Local::from(json);
-            let json = global.get(scope, json).unwrap();
-            let json = v8::Local::<v8::Object>::try_from(json).unwrap();
+        scope: &mut v8::HandleScope,
+    ) -> Result<JavaScriptResult, JavaScriptError> {
+        JavaScriptResult::from_encoded(value, scope, ValueEncoding::Json)
+    }
 
-            let stringify = v8::String::new(scope, "stringify").unwrap();
-            let stringify = v8::Local::from(stringify);
-            let stringify = json.get(scope, stringify).unwrap();
-            let stringify = v8::Local::<v8::Function>::try_from(stringify).unwrap();
+    pub fn from_encoded(
+        value: v8::Local<v8::Value>,
+        scope: &mut v8::HandleScope,
+        encoding: ValueEncoding,
+    ) -> Result<JavaScriptResult, JavaScriptError> {
+        if encoding == ValueEncoding::StructuredClone {
+            structured_clone::serialize(scope, value)
+                .map(JavaScriptResult::SerializedValue)
+                .map_err(|exception| JavaScriptError::new(exception, String::from("")))
+        } else if value.is_null() {
+            Ok(JavaScriptResult::NullValue)
+        } else if value.is_undefined() {
+            Ok(JavaScriptResult::UndefinedValue)
+        } else if let Ok(string_result) = v8::Local::<v8::String>::try_from(value) {
+            Ok(JavaScriptResult::StringValue(
+                string_result.to_rust_string_lossy(scope),
+            ))
+        } else if let Ok(number_result) = v8::Local::<v8::Number>::try_from(value) {
+            Ok(JavaScriptResult::NumberValue(number_result.value()))
+        } else if let Ok(bigint_result) = v8::Local::<v8::BigInt>::try_from(value) {
+            Ok(JavaScriptResult::BigIntValue(bigint_result.i64_value().0))
+        } else if value.is_boolean() {
+            Ok(JavaScriptResult::BoolValue(value.is_true()))
+        } else if let Ok(symbol) = v8::Local::<v8::Symbol>::try_from(value) {
+            let description = symbol.description(scope);
 
-            let string_result = stringify.call(scope, global.into(), &[value]).unwrap();
-            let string_result = string_result.to_string(scope).unwrap();
-            let string_result = string_result.to_rust_string_lossy(scope);
+            if description.is_undefined() {
+                Ok(JavaScriptResult::SymbolValue(None))
+            } else {
+                Ok(JavaScriptResult::SymbolValue(Some(
+                    description.to_rust_string_lossy(scope),
+                )))
+            }
+        } else if let Ok(function) = v8::Local::<v8::Function>::try_from(value) {
+            Ok(describe_function(scope, function))
+        } else if let Ok(date) = v8::Local::<v8::Date>::try_from(value) {
+            Ok(JavaScriptResult::DateValue {
+                epoch_milliseconds: date.value_of(),
+                json: legacy_json(scope, value),
+            })
+        } else if let Ok(regexp) = v8::Local::<v8::RegExp>::try_from(value) {
+            Ok(describe_regexp(scope, regexp))
+        } else if let Ok(view) = v8::Local::<v8::ArrayBufferView>::try_from(value) {
+            Ok(JavaScriptResult::BinaryValue {
+                bytes: copy_view_contents(view),
+                json: legacy_json(scope, value),
+            })
+        } else if let Ok(buffer) = v8::Local::<v8::ArrayBuffer>::try_from(value) {
+            let length = buffer.byte_length();
+
+            let view = v8::Uint8Array::new(scope, buffer, 0, length).ok_or_else(|| {
+                JavaScriptError::new(
+                    format!(
+                        "There was an issue marshaling the result: couldn't read {} bytes from the ArrayBuffer.",
+                        length
+                    ),
+                    String::from(""),
+                )
+            })?;
+
+            Ok(JavaScriptResult::BinaryValue {
+                bytes: copy_view_contents(view.into()),
+                json: legacy_json(scope, value),
+            })
+        } else {
+            let string_result = match encoding {
+                ValueEncoding::ExtendedJson => extended_json::stringify(scope, value),
+                _ => json_stringify(scope, value),
+            };
+
+            let string_result = string_result.map_err(|e| {
+                JavaScriptError::new(
+                    format!("There was an issue marshaling the result: {}", e),
+                    String::from(""),
+                )
+            })?;
 
             if value.is_array() {
-                JavaScriptResult::ArrayValue(string_result)
+                Ok(JavaScriptResult::ArrayValue(string_result))
             } else if value.is_object() {
-                JavaScriptResult::ObjectValue(string_result)
+                Ok(JavaScriptResult::ObjectValue(string_result))
             } else {
-                JavaScriptResult::StringValue(string_result)
+                Ok(JavaScriptResult::StringValue(string_result))
             }
         }
     }
@@ -95,356 +338,1408 @@ impl JavaScriptResult {
 pub struct JavaScriptError {
     pub exception: String,
     pub stack_trace: String,
-}
 
-pub struct V8Facade {
-    input: mpsc::Sender<Input>,
-    output: mpsc::Receiver<Output>,
-    pub handle: JoinHandle<Result<(), RecvError>>, // TODO: Signal the managed code that something happend here...
-}
+    // Set when an object was thrown and these were strings on it, which is always the case for an `Error`.
+    pub name: String,
+    pub message: String,
 
-impl V8Facade {
-    // https://github.com/denoland/rusty_v8/blob/584a0378002d2f952c55dd5f3d34ea2017ed0c7b/tests/test_api.rs#L565
-    fn eval<'s>(
-        scope: &mut v8::TryCatch<'s, v8::HandleScope>,
-        code: &str,
-    ) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
-        let source = v8::String::new(scope, &code).unwrap();
-        let script = v8::Script::compile(scope, source, None);
+    // The thrown object's own enumerable properties as JSON, e.g. the `code` a library assigned to an
+    // `Error`. `None` when something other than an object was thrown or they couldn't be stringified.
+    pub properties: Option<String>,
 
-        match script {
-            Some(script) => {
-                let scope = &mut v8::EscapableHandleScope::new(scope);
-                let r = script.run(scope);
-                Ok(r.map(|v| scope.escape(v)))
-            }
+    // The error's `cause`, which may have a cause of its own.
+    pub cause: Option<Box<JavaScriptError>>,
 
-            None => {
-                let exception = scope.exception().unwrap();
-                let exception = exception.to_rust_string_lossy(scope);
+    // The inner errors of an `AggregateError`.
+    pub errors: Vec<JavaScriptError>,
+}
 
-                Err(format!(
-                    "There was an issue compiling the provided script: {}",
-                    exception
-                ))
-            }
+// Cause chains can be cyclic, and nothing stops a script from building an arbitrarily deep one.
+const MAX_INNER_ERROR_DEPTH: usize = 32;
+
+impl JavaScriptError {
+    pub fn new(exception: String, stack_trace: String) -> JavaScriptError {
+        JavaScriptError {
+            exception,
+            stack_trace,
+            name: String::from(""),
+            message: String::from(""),
+            properties: None,
+            cause: None,
+            errors: Vec::new(),
         }
     }
 
-    fn call_func<'s>(
-        scope: &mut v8::HandleScope<'s>,
-        global: v8::Local<v8::Object>,
-        func_args: &FunctionCall,
-    ) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
-        let scope = &mut v8::EscapableHandleScope::new(scope);
+    pub(crate) fn from_exception(scope: &mut v8::TryCatch<v8::HandleScope>) -> JavaScriptError {
+        let exception = exception_message(scope);
+        let stack_trace = stack_trace(scope, &exception);
 
-        let func_name = v8::String::new(scope, &func_args.name).unwrap();
-        let func_name = v8::Local::from(func_name);
+        let mut error = JavaScriptError::new(exception, stack_trace);
 
-        let func = global.get(scope, func_name).unwrap();
-        let func = v8::Local::<v8::Function>::try_from(func).map_err(|_| {
-            format!(
-                "Couldn't resolve function `{}`, V8 returned: '{}'",
-                func_args.name,
-                func.to_rust_string_lossy(scope),
-            )
-        })?;
-
-        let args: Vec<v8::Local<v8::Value>> = func_args
-            .arguments
-            .iter()
-            .map(|p| -> v8::Local<v8::Value> {
-                match p {
-                    FunctionParameter::StringValue(v) => {
-                        v8::String::new(scope, v.as_str()).unwrap().into()
-                    }
+        if let Some(thrown) = scope.exception() {
+            // Reading `cause`, `errors` and friends can run getters, whatever they throw is dropped here
+            // rather than replacing the exception being reported.
+            let scope = &mut v8::HandleScope::new(scope);
+            let tc = &mut v8::TryCatch::new(scope);
 
-                    FunctionParameter::NumberValue(v) => v8::Number::new(scope, *v).into(),
+            let mut seen = vec![thrown];
 
-                    FunctionParameter::BigIntValue(v) => v8::BigInt::new_from_i64(scope, *v).into(),
+            error.describe_thrown_value(tc, thrown, &mut seen);
+        }
 
-                    FunctionParameter::BoolValue(v) => v8::Boolean::new(scope, *v).into(),
+        error
+    }
 
-                    FunctionParameter::SymbolValue(v) => {
-                        let desc = v8::String::new(scope, v.as_str());
+    // For values that weren't thrown, e.g. the reason a promise was rejected with.
+    pub(crate) fn from_value(
+        scope: &mut v8::HandleScope,
+        value: v8::Local<v8::Value>,
+    ) -> JavaScriptError {
+        let scope = &mut v8::HandleScope::new(scope);
+        let tc = &mut v8::TryCatch::new(scope);
 
-                        v8::Symbol::new(scope, desc).into()
-                    }
+        let mut seen = vec![value];
 
-                    FunctionParameter::ObjectValue(o) => {
-                        let object_json = v8::String::new(scope, o.as_str()).unwrap();
+        JavaScriptError::from_inner_value(tc, value, &mut seen)
+    }
 
-                        V8Facade::json_parse(object_json.into(), scope, global)
-                    }
-                }
-            })
-            .collect();
+    fn from_inner_value<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        value: v8::Local<'s, v8::Value>,
+        seen: &mut Vec<v8::Local<'s, v8::Value>>,
+    ) -> JavaScriptError {
+        let exception = value.to_rust_string_lossy(scope);
 
-        let args = args.as_slice();
+        let stack_trace = match v8::Local::<v8::Object>::try_from(value) {
+            Ok(object) if value.is_native_error() => get_property(scope, object, "stack")
+                .filter(|stack| stack.is_string())
+                .map(|stack| stack.to_rust_string_lossy(scope))
+                .unwrap_or_else(|| String::from("No stack trace was present.")),
 
-        let result = func.call(scope, global.into(), args);
-        Result::Ok(result.map(|v| scope.escape(v)))
+            _ => String::from("No stack trace was present."),
+        };
+
+        let mut error = JavaScriptError::new(exception, stack_trace);
+
+        error.describe_thrown_value(scope, value, seen);
+
+        error
     }
 
-    fn send_result_to_output(
-        result: Option<v8::Local<v8::Value>>,
-        scope: &mut v8::TryCatch<v8::HandleScope>,
-        global: v8::Local<v8::Object>,
-        tx_out: &mpsc::Sender<Output>,
+    fn describe_thrown_value<'s>(
+        &mut self,
+        scope: &mut v8::HandleScope<'s>,
+        thrown: v8::Local<'s, v8::Value>,
+        seen: &mut Vec<v8::Local<'s, v8::Value>>,
     ) {
-        match result {
-            Some(v) => {
-                let result = JavaScriptResult::from(v, scope, global);
+        let thrown = match v8::Local::<v8::Object>::try_from(thrown) {
+            Ok(thrown) => thrown,
+            _ => return,
+        };
 
-                tx_out.send(Output::Result(result)).unwrap();
-            }
+        if let Some(name) = get_string_property(scope, thrown, "name") {
+            self.name = name;
+        }
 
-            None => {
-                let exception = if let Some(exception) = scope.exception() {
-                    exception.to_rust_string_lossy(scope)
-                } else {
-                    String::from("No exception message was present.")
-                };
+        if let Some(message) = get_string_property(scope, thrown, "message") {
+            self.message = message;
+        }
 
-                let stack_trace = if let Some(stack_trace) = scope.stack_trace() {
-                    stack_trace.to_rust_string_lossy(scope)
-                } else {
-                    String::from("No stack trace was present.")
-                };
+        self.properties = own_properties_json(scope, thrown);
 
-                tx_out
-                    .send(Output::Error(JavaScriptError {
-                        exception,
-                        stack_trace,
-                    }))
-                    .unwrap();
-            }
+        if !thrown.is_native_error() || seen.len() >= MAX_INNER_ERROR_DEPTH {
+            return;
         }
-    }
 
-    fn send_result_to_delegate<F: FnOnce(Output) + 'static>(
-        result: Option<v8::Local<v8::Value>>,
-        scope: &mut v8::TryCatch<v8::HandleScope>,
-        global: v8::Local<v8::Object>,
-        on_complete: F,
-    ) {
-        match result {
-            Some(v) => {
-                let result = JavaScriptResult::from(v, scope, global);
+        if let Some(cause) = get_own_property(scope, thrown, "cause") {
+            if !seen.iter().any(|s| s.strict_equals(cause)) {
+                seen.push(cause);
 
-                on_complete(Output::Result(result));
+                self.cause = Some(Box::new(JavaScriptError::from_inner_value(
+                    scope, cause, seen,
+                )));
+
+                seen.pop();
             }
+        }
 
-            None => {
-                let exception = if let Some(exception) = scope.exception() {
-                    exception.to_rust_string_lossy(scope)
-                } else {
-                    String::from("No exception message was present.")
-                };
+        let errors = get_own_property(scope, thrown, "errors")
+            .and_then(|errors| v8::Local::<v8::Array>::try_from(errors).ok());
 
-                let stack_trace = if let Some(stack_trace) = scope.stack_trace() {
-                    stack_trace.to_rust_string_lossy(scope)
-                } else {
-                    String::from("No stack trace was present.")
+        if let Some(errors) = errors {
+            for i in 0..errors.length() {
+                let inner = match errors.get_index(scope, i) {
+                    Some(inner) => inner,
+                    None => continue,
                 };
 
-                let result = JavaScriptError {
-                    exception,
-                    stack_trace,
-                };
+                if seen.iter().any(|s| s.strict_equals(inner)) {
+                    continue;
+                }
+
+                seen.push(inner);
 
-                on_complete(Output::Error(result));
+                self.errors
+                    .push(JavaScriptError::from_inner_value(scope, inner, seen));
+
+                seen.pop();
             }
         }
     }
+}
 
-    fn json_parse<'s>(
-        json_value: v8::Local<v8::Value>,
-        scope: &mut v8::HandleScope<'s>,
-        global: v8::Local<v8::Object>,
-    ) -> v8::Local<'s, v8::Value> {
-        let scope = &mut v8::EscapableHandleScope::new(scope);
+fn get_property<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<v8::Object>,
+    name: &str,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let key = v8::String::new(scope, name)?;
+
+    object.get(scope, key.into())
+}
 
-        let json = v8::String::new(scope, "JSON").unwrap();
-        let json = v8::Local::from(json);
-        let json = global.get(scope, json).unwrap();
-        let json = v8::Local::<v8::Object>::try_from(json).unwrap();
+fn get_string_property(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+    name: &str,
+) -> Option<String> {
+    get_property(scope, object, name)
+        .filter(|value| value.is_string())
+        .map(|value| value.to_rust_string_lossy(scope))
+}
 
-        let parse = v8::String::new(scope, "parse").unwrap();
-        let parse = v8::Local::from(parse);
-        let parse = json.get(scope, parse).unwrap();
-        let parse = v8::Local::<v8::Function>::try_from(parse).unwrap();
+// The properties are copied onto a plain object first so a `toJSON` on the thrown value doesn't decide
+// what gets reported.
+fn own_properties_json(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+) -> Option<String> {
+    let keys = object.get_own_property_names(
+        scope,
+        v8::GetPropertyNamesArgs {
+            key_conversion: v8::KeyConversionMode::ConvertToString,
+            ..Default::default()
+        },
+    )?;
+
+    let copy = v8::Object::new(scope);
+
+    for i in 0..keys.length() {
+        let key = keys.get_index(scope, i)?;
+        let value = object.get(scope, key)?;
+        let key = v8::Local::<v8::Name>::try_from(key).ok()?;
+
+        copy.create_data_property(scope, key, value)?;
+    }
 
-        let parse_result = parse.call(scope, global.into(), &[json_value]).unwrap();
+    json_stringify(scope, copy.into()).ok()
+}
 
-        scope.escape(parse_result)
+fn get_own_property<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    object: v8::Local<v8::Object>,
+    name: &str,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let key = v8::String::new(scope, name)?;
+
+    if object.has_own_property(scope, key.into())? {
+        object.get(scope, key.into())
+    } else {
+        None
     }
+}
 
-    pub fn new() -> Self {
-        INIT_PLATFORM.call_once(init_platform);
+// `v8::json` goes straight to V8's internal JSON implementation, so a script that replaces the global
+// `JSON` object can't change how values are marshaled.
+fn json_stringify(
+    scope: &mut v8::HandleScope,
+    value: v8::Local<v8::Value>,
+) -> Result<String, String> {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    match v8::json::stringify(tc, value) {
+        Some(json) => Ok(json.to_rust_string_lossy(tc)),
+        None => Err(exception_message(tc)),
+    }
+}
 
-        let (tx_in, rx_in) = mpsc::channel::<Input>();
-        let (tx_out, rx_out) = mpsc::channel::<Output>();
+pub(crate) fn new_string<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: &str,
+) -> Result<v8::Local<'s, v8::String>, String> {
+    v8::String::new(scope, value).ok_or_else(|| {
+        format!(
+            "Couldn't create a V8 string from {} bytes, it is larger than V8 allows.",
+            value.len()
+        )
+    })
+}
 
-        let handle = std::thread::spawn(move || {
-            let isolate = &mut v8::Isolate::new(Default::default());
-            let scope = &mut v8::HandleScope::new(isolate);
-            let context = v8::Context::new(scope);
+fn new_utf16_string<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: &[u16],
+) -> Result<v8::Local<'s, v8::String>, String> {
+    v8::String::new_from_two_byte(scope, value, v8::NewStringType::Normal).ok_or_else(|| {
+        format!(
+            "Couldn't create a V8 string from {} UTF-16 code units, it is larger than V8 allows.",
+            value.len()
+        )
+    })
+}
 
-            loop {
-                let input = rx_in.recv()?;
+fn read_utf16(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> Vec<u16> {
+    match v8::Local::<v8::String>::try_from(value) {
+        Ok(string) => {
+            let mut buffer = vec![0; string.length()];
+            let written =
+                string.write(scope, &mut buffer, 0, v8::WriteOptions::NO_NULL_TERMINATION);
+            buffer.truncate(written);
 
-                let scope = &mut v8::HandleScope::new(scope);
-                let scope = &mut v8::ContextScope::new(scope, context);
+            buffer
+        }
 
-                let global = context.global(scope);
+        Err(_) => Vec::new(),
+    }
+}
 
-                match input {
-                    Input::Source(code) => {
-                        let tc = &mut v8::TryCatch::new(scope);
-                        let result = V8Facade::eval(tc, code.as_str());
+// The bytes are moved into the backing store, V8 takes ownership of the allocation without copying it.
+pub(crate) fn new_array_buffer<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    bytes: Vec<u8>,
+) -> v8::Local<'s, v8::ArrayBuffer> {
+    let backing_store =
+        v8::ArrayBuffer::new_backing_store_from_boxed_slice(bytes.into_boxed_slice()).make_shared();
 
-                        match result {
-                            Ok(result) => {
-                                V8Facade::send_result_to_output(result, tc, global, &tx_out);
-                            }
+    v8::ArrayBuffer::with_backing_store(scope, &backing_store)
+}
 
-                            Err(error) => {
-                                let error = JavaScriptError {
-                                    exception: error,
-                                    stack_trace: String::from(""),
-                                };
+fn copy_view_contents(view: v8::Local<v8::ArrayBufferView>) -> Vec<u8> {
+    let mut bytes = vec![0; view.byte_length()];
+    let copied = view.copy_contents(&mut bytes);
+    bytes.truncate(copied);
 
-                                tx_out.send(Output::Error(error)).unwrap();
-                            }
-                        }
-                    }
+    bytes
+}
 
-                    Input::BeginSource(code, on_complete) => {
-                        let tc = &mut v8::TryCatch::new(scope);
-                        let result = V8Facade::eval(tc, code.as_str());
+// What `JSON.stringify` makes of a value, for results that older hosts have only ever seen as JSON.
+fn legacy_json(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> Option<String> {
+    let tc = &mut v8::TryCatch::new(scope);
 
-                        match result {
-                            Ok(result) => {
-                                V8Facade::send_result_to_delegate(result, tc, global, on_complete);
-                            }
+    v8::json::stringify(tc, value).map(|json| json.to_rust_string_lossy(tc))
+}
 
-                            Err(error) => {
-                                let error = JavaScriptError {
-                                    exception: error,
-                                    stack_trace: String::from(""),
-                                };
+// Classes are functions too. The source comes from the `Function.prototype.toString` captured before
+// any script ran, so neither it nor `name` can be spoofed by overriding methods on the function.
+fn describe_function(
+    scope: &mut v8::HandleScope,
+    function: v8::Local<v8::Function>,
+) -> JavaScriptResult {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let name = function.get_name(tc).to_rust_string_lossy(tc);
+
+    // `length` is configurable, anything other than a plain unsigned integer is reported as 0.
+    let arity = match v8::String::new(tc, "length").and_then(|key| function.get(tc, key.into())) {
+        Some(length) => v8::Local::<v8::Uint32>::try_from(length)
+            .map(|length| length.value())
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    let source = match intrinsics::get_function(tc, "functionToString") {
+        Some(to_string) => match to_string.call(tc, function.into(), &[]) {
+            Some(source) => source.to_rust_string_lossy(tc),
+            None => String::from(""),
+        },
+        None => String::from(""),
+    };
+
+    JavaScriptResult::FunctionValue {
+        name,
+        arity,
+        source,
+    }
+}
 
-                                on_complete(Output::Error(error));
-                            }
-                        }
-                    }
+fn describe_regexp(scope: &mut v8::HandleScope, regexp: v8::Local<v8::RegExp>) -> JavaScriptResult {
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let pattern = match intrinsics::get_function(tc, "regExpSource") {
+        Some(get_source) => match get_source.call(tc, regexp.into(), &[]) {
+            Some(source) => source.to_rust_string_lossy(tc),
+            None => String::from(""),
+        },
+        None => String::from(""),
+    };
+
+    let flags = match intrinsics::get_function(tc, "regExpFlags") {
+        Some(get_flags) => match get_flags.call(tc, regexp.into(), &[]) {
+            Some(flags) => flags.to_rust_string_lossy(tc),
+            None => String::from(""),
+        },
+        None => String::from(""),
+    };
+
+    let json = legacy_json(tc, regexp.into());
+
+    JavaScriptResult::RegExpValue {
+        pattern,
+        flags,
+        json,
+    }
+}
 
-                    Input::Function(func_args) => {
-                        let tc = &mut v8::TryCatch::new(scope);
-                        let result = V8Facade::call_func(tc, global, &func_args);
+pub(crate) fn exception_message(scope: &mut v8::TryCatch<v8::HandleScope>) -> String {
+    if let Some(exception) = scope.exception() {
+        exception.to_rust_string_lossy(scope)
+    } else {
+        String::from("No exception message was present.")
+    }
+}
 
-                        match result {
-                            Ok(result) => {
-                                V8Facade::send_result_to_output(result, tc, global, &tx_out)
-                            }
+pub(crate) fn module_origin<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+) -> Result<v8::ScriptOrigin<'s>, String> {
+    let name = new_string(scope, name)?;
+    let source_map_url = v8::undefined(scope);
+
+    Ok(v8::ScriptOrigin::new(
+        scope,
+        name.into(),
+        0,
+        0,
+        false,
+        0,
+        source_map_url.into(),
+        false,
+        false,
+        true,
+    ))
+}
 
-                            Err(error) => {
-                                let error = JavaScriptError {
-                                    exception: error,
-                                    stack_trace: String::from(""),
-                                };
+// Errors carry their own `stack`. Anything else that was thrown falls back to the frames V8 captured
+// for the message, formatted the way `stack` would have been.
+fn stack_trace(scope: &mut v8::TryCatch<v8::HandleScope>, exception: &str) -> String {
+    if let Some(stack_trace) = scope.stack_trace() {
+        return stack_trace.to_rust_string_lossy(scope);
+    }
 
-                                tx_out.send(Output::Error(error)).unwrap();
-                            }
-                        };
-                    }
+    let frames = match scope.message() {
+        Some(message) => message.get_stack_trace(scope),
+        None => None,
+    };
 
-                    Input::BeginFunction(func_args, on_complete) => {
-                        let tc = &mut v8::TryCatch::new(scope);
-                        let result = V8Facade::call_func(tc, global, &func_args);
+    let frames = match frames {
+        Some(frames) if frames.get_frame_count() > 0 => frames,
+        _ => return String::from("No stack trace was present."),
+    };
 
-                        match result {
-                            Ok(result) => {
-                                V8Facade::send_result_to_delegate(result, tc, global, on_complete)
-                            }
+    format_stack_trace(scope, exception, frames)
+}
 
-                            Err(error) => {
-                                let error = JavaScriptError {
-                                    exception: error,
-                                    stack_trace: String::from(""),
-                                };
+// In the same shape as V8's own `stack` property.
+pub(crate) fn format_stack_trace(
+    scope: &mut v8::HandleScope,
+    exception: &str,
+    frames: v8::Local<v8::StackTrace>,
+) -> String {
+    let mut stack_trace = String::from(exception);
+
+    for i in 0..frames.get_frame_count() {
+        let frame = match frames.get_frame(scope, i) {
+            Some(frame) => frame,
+            None => continue,
+        };
+
+        let script_name = match frame.get_script_name(scope) {
+            Some(script_name) => script_name.to_rust_string_lossy(scope),
+            None => String::from("<anonymous>"),
+        };
+
+        let location = format!(
+            "{}:{}:{}",
+            script_name,
+            frame.get_line_number(),
+            frame.get_column()
+        );
 
-                                on_complete(Output::Error(error));
-                            }
-                        };
-                    }
+        match frame.get_function_name(scope) {
+            Some(function_name) if function_name.length() > 0 => {
+                let function_name = function_name.to_rust_string_lossy(scope);
 
-                    Input::HeapReport => {
-                        let heap_stats = &mut v8::HeapStatistics::default();
+                stack_trace.push_str(&format!("\n    at {} ({})", function_name, location));
+            }
 
-                        scope.get_heap_statistics(heap_stats);
+            _ => stack_trace.push_str(&format!("\n    at {}", location)),
+        }
+    }
 
-                        let heap_stats = V8HeapStatistics {
-                            total_heap_size: heap_stats.total_heap_size(),
-                            total_heap_size_executable: heap_stats.total_heap_size_executable(),
-                            total_physical_size: heap_stats.total_physical_size(),
-                            total_available_size: heap_stats.total_available_size(),
-                            used_heap_size: heap_stats.used_heap_size(),
-                            heap_size_limit: heap_stats.heap_size_limit(),
-                            malloced_memory: heap_stats.malloced_memory(),
-                            does_zap_garbage: heap_stats.does_zap_garbage(),
-                            number_of_native_contexts: heap_stats.number_of_native_contexts(),
-                            number_of_detached_contexts: heap_stats.number_of_detached_contexts(),
-                            peak_malloced_memory: heap_stats.peak_malloced_memory(),
-                            used_global_handles_size: heap_stats.used_global_handles_size(),
-                            total_global_handles_size: heap_stats.total_global_handles_size(),
-                        };
+    stack_trace
+}
 
-                        tx_out.send(Output::HeapStatistics(heap_stats)).unwrap();
-                    }
+fn run_microtask(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _rv: v8::ReturnValue,
+) {
+    let task = match v8::Local::<v8::Array>::try_from(args.data()) {
+        Ok(task) => task,
+        Err(_) => return,
+    };
+
+    let func = match task
+        .get_index(scope, 0)
+        .map(v8::Local::<v8::Function>::try_from)
+    {
+        Some(Ok(func)) => func,
+        _ => return,
+    };
+
+    let arguments: Vec<v8::Local<v8::Value>> = (1..task.length())
+        .filter_map(|i| task.get_index(scope, i))
+        .collect();
+
+    let tc = &mut v8::TryCatch::new(scope);
+
+    let receiver = tc.get_current_context().global(tc);
+
+    if func.call(tc, receiver.into(), &arguments).is_none() {
+        // Nothing can catch what a task the host queued throws, so it's handed to a promise that's
+        // rejected without a handler and reported like any other.
+        if let (Some(exception), Some(resolver)) = (tc.exception(), v8::PromiseResolver::new(tc)) {
+            tc.reset();
+            resolver.reject(tc, exception);
+        }
+    }
+}
 
-                    Input::BeginHeapReport(on_complete) => {
-                        let heap_stats = &mut v8::HeapStatistics::default();
+// What the worker's context is set up with. Fixed once the facade is created.
+#[derive(Clone, Debug, Default)]
+pub struct V8FacadeOptions {
+    // Installs a CommonJS `require` that loads modules from under this directory, see `commonjs`.
+    pub commonjs_root: Option<PathBuf>,
 
-                        scope.get_heap_statistics(heap_stats);
+    // Removes the `WebAssembly` global, and with it every way to compile Wasm, the facade's included.
+    pub disable_wasm: bool,
 
-                        let heap_stats = V8HeapStatistics {
-                            total_heap_size: heap_stats.total_heap_size(),
-                            total_heap_size_executable: heap_stats.total_heap_size_executable(),
-                            total_physical_size: heap_stats.total_physical_size(),
-                            total_available_size: heap_stats.total_available_size(),
-                            used_heap_size: heap_stats.used_heap_size(),
-                            heap_size_limit: heap_stats.heap_size_limit(),
-                            malloced_memory: heap_stats.malloced_memory(),
-                            does_zap_garbage: heap_stats.does_zap_garbage(),
-                            number_of_native_contexts: heap_stats.number_of_native_contexts(),
-                            number_of_detached_contexts: heap_stats.number_of_detached_contexts(),
-                            peak_malloced_memory: heap_stats.peak_malloced_memory(),
-                            used_global_handles_size: heap_stats.used_global_handles_size(),
-                            total_global_handles_size: heap_stats.total_global_handles_size(),
-                        };
+    // `eval`, `new Function` and the like throw an `EvalError`. Source the host hands the facade still
+    // runs.
+    pub disallow_code_generation: bool,
 
-                        // let heap_stats = Box::into_raw(Box::new(heap_stats));
+    // When set, only these of the globals the context starts with are kept, the facade's own `setTimeout`
+    // and `require` included. See `globals` for what's left.
+    pub allowed_globals: Option<Vec<String>>,
 
-                        on_complete(heap_stats);
-                    }
+    // Added after the allow-list is applied.
+    pub host_globals: Vec<HostGlobal>,
 
-                    Input::Shutdown => break Ok(()),
-                };
-            }
-        });
+    // Seeds `Math.random` and fixes the clock, time zone and locale, see `deterministic`.
+    pub deterministic: Option<DeterministicOptions>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeterministicOptions {
+    pub random_seed: u64,
+
+    // Milliseconds since the epoch, what `Date.now()` reports until the host calls `set_time`.
+    pub time: f64,
+
+    // What `Intl` and the `toLocale...` methods use when they aren't given a locale.
+    pub locale: String,
+}
+
+impl Default for DeterministicOptions {
+    fn default() -> Self {
+        DeterministicOptions {
+            random_seed: 0,
+            time: 0.0,
+            locale: String::from("en-US"),
+        }
+    }
+}
+
+pub struct V8Facade {
+    input: mpsc::Sender<Input>,
+    output: mpsc::Receiver<Output>,
+    pub handle: JoinHandle<Result<(), RecvError>>, // TODO: Signal the managed code that something happend here...
+}
+
+impl V8Facade {
+    // https://github.com/denoland/rusty_v8/blob/584a0378002d2f952c55dd5f3d34ea2017ed0c7b/tests/test_api.rs#L565
+    fn eval<'s>(
+        scope: &mut v8::TryCatch<'s, v8::HandleScope>,
+        code: &SourceText,
+    ) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
+        let source = match code {
+            SourceText::Utf8(code) => new_string(scope, code)?,
+            SourceText::Utf16(code) => new_utf16_string(scope, code)?,
+        };
+        let script = v8::Script::compile(scope, source, None);
+
+        match script {
+            Some(script) => {
+                let scope = &mut v8::EscapableHandleScope::new(scope);
+                let r = script.run(scope);
+                Ok(r.map(|v| scope.escape(v)))
+            }
+
+            None => Err(format!(
+                "There was an issue compiling the provided script: {}",
+                exception_message(scope)
+            )),
+        }
+    }
+
+    // V8 stops at the first syntax error, so there is at most one diagnostic.
+    fn check_source_syntax(
+        scope: &mut v8::TryCatch<v8::HandleScope>,
+        code: &SourceText,
+        kind: SourceKind,
+    ) -> Result<Vec<SyntaxDiagnostic>, String> {
+        let source = match code {
+            SourceText::Utf8(code) => new_string(scope, code)?,
+            SourceText::Utf16(code) => new_utf16_string(scope, code)?,
+        };
+
+        let compiled = match kind {
+            SourceKind::Script => v8::Script::compile(scope, source, None).is_some(),
+
+            SourceKind::Module => {
+                let origin = module_origin(scope, "<anonymous>")?;
+                let source = v8::script_compiler::Source::new(source, Some(&origin));
+
+                v8::script_compiler::compile_module(scope, source).is_some()
+            }
+        };
+
+        if compiled {
+            return Ok(Vec::new());
+        }
+
+        match scope.message() {
+            Some(message) => Ok(vec![SyntaxDiagnostic::from_message(scope, message)]),
+
+            None => Err(format!(
+                "There was an issue compiling the provided source: {}",
+                exception_message(scope)
+            )),
+        }
+    }
+
+    fn call_func<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        global: v8::Local<v8::Object>,
+        func_args: FunctionCall,
+        encoding: ValueEncoding,
+    ) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
+        let scope = &mut v8::EscapableHandleScope::new(scope);
+
+        let FunctionCall { name, arguments } = func_args;
+
+        let func = V8Facade::resolve_function(scope, global, &name)?;
+
+        let mut args: Vec<v8::Local<v8::Value>> = Vec::with_capacity(arguments.len());
+
+        for p in arguments.into_iter() {
+            args.push(V8Facade::to_v8_value(scope, p, encoding)?);
+        }
+
+        let args = args.as_slice();
+
+        let result = func.call(scope, global.into(), args);
+        Result::Ok(result.map(|v| scope.escape(v)))
+    }
+
+    fn resolve_function<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        global: v8::Local<v8::Object>,
+        name: &str,
+    ) -> Result<v8::Local<'s, v8::Function>, String> {
+        let func_name = new_string(scope, name)?;
+        let func_name = v8::Local::from(func_name);
+
+        let func = global
+            .get(scope, func_name)
+            .ok_or_else(|| format!("Couldn't resolve function `{}`.", name))?;
+
+        v8::Local::<v8::Function>::try_from(func).map_err(|_| {
+            format!(
+                "Couldn't resolve function `{}`, V8 returned: '{}'",
+                name,
+                func.to_rust_string_lossy(scope),
+            )
+        })
+    }
+
+    // Queues a call to a global function behind whatever microtasks are already waiting. The function
+    // and its arguments are kept in an array that's the data of the task itself.
+    fn queue_microtask(
+        scope: &mut v8::HandleScope,
+        global: v8::Local<v8::Object>,
+        func_args: FunctionCall,
+    ) -> Result<(), String> {
+        let FunctionCall { name, arguments } = func_args;
+
+        let mut task = vec![V8Facade::resolve_function(scope, global, &name)?.into()];
+
+        for p in arguments.into_iter() {
+            task.push(V8Facade::to_v8_value(scope, p, ValueEncoding::Json)?);
+        }
+
+        let task = v8::Array::new_with_elements(scope, &task);
+
+        let task = v8::Function::builder(run_microtask)
+            .data(task.into())
+            .build(scope)
+            .ok_or_else(|| String::from("Couldn't create the microtask."))?;
+
+        scope.enqueue_microtask(task);
+
+        Ok(())
+    }
+
+    fn call_wasm_export<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        module: &str,
+        func_args: FunctionCall,
+    ) -> Result<Option<v8::Local<'s, v8::Value>>, String> {
+        let scope = &mut v8::EscapableHandleScope::new(scope);
+
+        let FunctionCall { name, arguments } = func_args;
+
+        let mut args: Vec<v8::Local<v8::Value>> = Vec::with_capacity(arguments.len());
+
+        for p in arguments.into_iter() {
+            match p {
+                FunctionParameter::NumberValue(_) | FunctionParameter::BigIntValue(_) => {
+                    args.push(V8Facade::to_v8_value(scope, p, ValueEncoding::Json)?)
+                }
+
+                _ => {
+                    return Err(String::from(
+                        "Only numbers and BigInts can be passed to Wasm functions.",
+                    ))
+                }
+            }
+        }
+
+        let result = wasm::call(scope, module, &name, &args);
+        Ok(result.map(|v| scope.escape(v)))
+    }
+
+    fn to_v8_value<'s>(
+        scope: &mut v8::HandleScope<'s>,
+        parameter: FunctionParameter,
+        encoding: ValueEncoding,
+    ) -> Result<v8::Local<'s, v8::Value>, String> {
+        let value = match parameter {
+            FunctionParameter::NullValue => v8::null(scope).into(),
+
+            FunctionParameter::UndefinedValue => v8::undefined(scope).into(),
+
+            FunctionParameter::StringValue(v) => new_string(scope, v.as_str())?.into(),
+
+            FunctionParameter::Utf16StringValue(v) => new_utf16_string(scope, &v)?.into(),
+
+            FunctionParameter::NumberValue(v) => v8::Number::new(scope, v).into(),
+
+            FunctionParameter::BigIntValue(v) => v8::BigInt::new_from_i64(scope, v).into(),
+
+            FunctionParameter::BoolValue(v) => v8::Boolean::new(scope, v).into(),
+
+            FunctionParameter::SymbolValue(v) => {
+                let desc = new_string(scope, v.as_str())?;
+
+                v8::Symbol::new(scope, Some(desc)).into()
+            }
+
+            FunctionParameter::ObjectValue(o) => V8Facade::json_parse(o.as_str(), scope, encoding)?,
+
+            FunctionParameter::ArrayBufferValue(bytes) => new_array_buffer(scope, bytes).into(),
+
+            FunctionParameter::Uint8ArrayValue(bytes) => {
+                let length = bytes.len();
+                let buffer = new_array_buffer(scope, bytes);
+
+                v8::Uint8Array::new(scope, buffer, 0, length)
+                    .ok_or_else(|| format!("Couldn't create a Uint8Array over {} bytes.", length))?
+                    .into()
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn to_output(
+        result: Option<v8::Local<v8::Value>>,
+        scope: &mut v8::TryCatch<v8::HandleScope>,
+        marshaling: Marshaling,
+    ) -> Output {
+        match result {
+            Some(v) if v.is_string() && marshaling.strings == StringEncoding::Utf16 => {
+                Output::Result(JavaScriptResult::Utf16StringValue(read_utf16(scope, v)))
+            }
+
+            Some(v) => {
+                let result = JavaScriptResult::from_encoded(v, scope, marshaling.values);
+
+                match result {
+                    Ok(result) => Output::Result(result),
+                    Err(error) => Output::Error(error),
+                }
+            }
+
+            None => Output::Error(JavaScriptError::from_exception(scope)),
+        }
+    }
+
+    // The result of a script run with top-level await is a promise, microtasks and timers are run until
+    // it settles. Nothing else can settle it once both are done.
+    fn to_settled_output(
+        result: Option<v8::Local<v8::Value>>,
+        scope: &mut v8::TryCatch<v8::HandleScope>,
+        marshaling: Marshaling,
+        timeout: Duration,
+    ) -> Output {
+        let promise = match result.map(v8::Local::<v8::Promise>::try_from) {
+            Some(Ok(promise)) => promise,
+            _ => return V8Facade::to_output(result, scope, marshaling),
+        };
+
+        let settled = || promise.state() != v8::PromiseState::Pending;
+
+        if let Err(error) = V8Facade::run_timers_until(scope, timeout, settled) {
+            return Output::Error(error);
+        }
+
+        match promise.state() {
+            v8::PromiseState::Fulfilled => {
+                let value = promise.result(scope);
+
+                V8Facade::to_output(Some(value), scope, marshaling)
+            }
+
+            v8::PromiseState::Rejected => {
+                let reason = promise.result(scope);
+
+                rejections::forget(scope, promise);
+
+                Output::Error(JavaScriptError::from_value(scope, reason))
+            }
+
+            v8::PromiseState::Pending => Output::Error(JavaScriptError::new(
+                String::from(
+                    "The script is still awaiting a promise that nothing is left to settle.",
+                ),
+                String::from(""),
+            )),
+        }
+    }
+
+    // Runs timers as they come due, until none are left.
+    fn run_until_idle_or_timeout(
+        scope: &mut v8::HandleScope,
+        timeout: Duration,
+    ) -> Result<(), JavaScriptError> {
+        V8Facade::run_timers_until(scope, timeout, || false)
+    }
+
+    // Runs timers as they come due, until `done` or none are left. It's an error when the next one isn't
+    // due before the timeout.
+    fn run_timers_until(
+        scope: &mut v8::HandleScope,
+        timeout: Duration,
+        done: impl Fn() -> bool,
+    ) -> Result<(), JavaScriptError> {
+        let deadline = Instant::now() + timeout;
+
+        scope.perform_microtask_checkpoint();
+
+        while let Some(due) = timers::next_due(scope) {
+            if done() {
+                break;
+            }
+
+            if due > deadline {
+                return Err(JavaScriptError::new(
+                    format!(
+                        "The script was still waiting on timers after {} ms.",
+                        timeout.as_millis()
+                    ),
+                    String::from(""),
+                ));
+            }
+
+            let now = Instant::now();
+
+            if due > now {
+                std::thread::sleep(due - now);
+            }
+
+            if let Some(error) = timers::run_due(scope) {
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Nothing is waiting on a timer that fires between inputs, what it throws goes to the rejection
+    // handler along with the rejections it leaves unhandled. Without a handler both are dropped.
+    fn run_due_timers(scope: &mut v8::HandleScope) {
+        if timers::next_due(scope).map_or(false, |due| due <= Instant::now()) {
+            let thrown = timers::run_due(scope);
+
+            rejections::report_to_handler(scope, thrown);
+        }
+    }
+
+    fn send_result_to_output(
+        result: Option<v8::Local<v8::Value>>,
+        scope: &mut v8::TryCatch<v8::HandleScope>,
+        marshaling: Marshaling,
+        tx_out: &mpsc::Sender<Output>,
+    ) {
+        let output = V8Facade::to_output(result, scope, marshaling);
+        let output = rejections::report(scope, output);
+
+        tx_out.send(output).unwrap();
+    }
+
+    fn send_result_to_delegate<F: FnOnce(Output) + 'static>(
+        result: Option<v8::Local<v8::Value>>,
+        scope: &mut v8::TryCatch<v8::HandleScope>,
+        marshaling: Marshaling,
+        on_complete: F,
+    ) {
+        let output = V8Facade::to_output(result, scope, marshaling);
+        let output = rejections::report(scope, output);
+
+        on_complete(output);
+    }
+
+    fn json_parse<'s>(
+        json: &str,
+        scope: &mut v8::HandleScope<'s>,
+        encoding: ValueEncoding,
+    ) -> Result<v8::Local<'s, v8::Value>, String> {
+        let tc = &mut v8::TryCatch::new(scope);
+
+        let json = new_string(tc, json)?;
+
+        let value = match encoding {
+            ValueEncoding::ExtendedJson => extended_json::parse(tc, json),
+            _ => v8::json::parse(tc, json).ok_or_else(|| exception_message(tc)),
+        };
+
+        value.map_err(|e| format!("There was an issue parsing the provided JSON: {}", e))
+    }
+
+    // Initializes V8 for the whole process, with `flags` as they'd be given on the command line and
+    // `thread_pool_size` background threads for the platform, 0 for one per core. Only works before
+    // the first facade is created, which otherwise initializes V8 with the defaults.
+    pub fn initialize(flags: &str, thread_pool_size: u32) -> Result<(), String> {
+        let mut initialized = false;
+
+        INIT_PLATFORM.call_once(|| {
+            init_platform(flags, thread_pool_size);
+            initialized = true;
+        });
+
+        if initialized {
+            Ok(())
+        } else {
+            Err(String::from("V8 has already been initialized."))
+        }
+    }
+
+    pub fn new() -> Self {
+        V8Facade::with_options(V8FacadeOptions::default())
+    }
+
+    pub fn with_options(options: V8FacadeOptions) -> Self {
+        INIT_PLATFORM.call_once(|| init_platform("", 0));
+
+        let (tx_in, rx_in) = mpsc::channel::<Input>();
+        let (tx_out, rx_out) = mpsc::channel::<Output>();
+
+        let handle = std::thread::spawn(move || {
+            let isolate = &mut v8::Isolate::new(Default::default());
+            isolate.set_capture_stack_trace_for_uncaught_exceptions(true, STACK_TRACE_FRAME_LIMIT);
+            rejections::install(isolate);
+            modules::install(isolate);
+            let scope = &mut v8::HandleScope::new(isolate);
+            let context = v8::Context::new(scope);
+
+            if options.disallow_code_generation {
+                disallow_code_generation(context);
+            }
+
+            {
+                let scope = &mut v8::ContextScope::new(scope, context);
+
+                if options.disable_wasm {
+                    wasm::disable(scope);
+                }
+
+                intrinsics::install(scope);
+
+                if let Some(deterministic) = &options.deterministic {
+                    deterministic::install(scope, deterministic);
+                }
+                timers::install(scope);
+
+                if let Some(root) = &options.commonjs_root {
+                    commonjs::install(scope, root);
+                }
+
+                if let Some(allowed) = &options.allowed_globals {
+                    globals::retain(scope, allowed);
+                }
+
+                globals::define(scope, &options.host_globals);
+            }
+
+            loop {
+                // Timers that came due while the last input was handled don't wait for the queue of
+                // inputs to drain.
+                {
+                    let scope = &mut v8::HandleScope::new(scope);
+                    let scope = &mut v8::ContextScope::new(scope, context);
+
+                    V8Facade::run_due_timers(scope);
+                }
+
+                // Waits for the next input, or until the next timer comes due.
+                let input = match timers::next_due(scope) {
+                    Some(due) => {
+                        match rx_in.recv_timeout(due.saturating_duration_since(Instant::now())) {
+                            Ok(input) => Some(input),
+                            Err(mpsc::RecvTimeoutError::Timeout) => None,
+                            Err(mpsc::RecvTimeoutError::Disconnected) => return Err(RecvError),
+                        }
+                    }
+
+                    None => Some(rx_in.recv()?),
+                };
+
+                let scope = &mut v8::HandleScope::new(scope);
+                let scope = &mut v8::ContextScope::new(scope, context);
+
+                V8Facade::run_due_timers(scope);
+
+                let input = match input {
+                    Some(input) => input,
+                    None => continue,
+                };
+
+                let global = context.global(scope);
+
+                match input {
+                    Input::Source(code, marshaling) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = V8Facade::eval(tc, &code);
+
+                        match result {
+                            Ok(result) => {
+                                V8Facade::send_result_to_output(result, tc, marshaling, &tx_out);
+                            }
+
+                            Err(error) => {
+                                let error = JavaScriptError::new(error, String::from(""));
+
+                                tx_out.send(Output::Error(error)).unwrap();
+                            }
+                        }
+                    }
+
+                    Input::AwaitSource(code, marshaling, timeout) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let code = SourceText::Utf8(top_level_await::wrap(tc, &code));
+
+                        let output = match V8Facade::eval(tc, &code) {
+                            Ok(result) => {
+                                V8Facade::to_settled_output(result, tc, marshaling, timeout)
+                            }
+                            Err(error) => {
+                                Output::Error(JavaScriptError::new(error, String::from("")))
+                            }
+                        };
+
+                        let output = rejections::report(tc, output);
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::SourceUntilIdle(code, marshaling, timeout) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+
+                        let output = match V8Facade::eval(tc, &code) {
+                            Ok(Some(result)) => {
+                                match V8Facade::run_until_idle_or_timeout(tc, timeout) {
+                                    Ok(()) => V8Facade::to_settled_output(
+                                        Some(result),
+                                        tc,
+                                        marshaling,
+                                        timeout,
+                                    ),
+                                    Err(error) => Output::Error(error),
+                                }
+                            }
+                            Ok(None) => V8Facade::to_output(None, tc, marshaling),
+                            Err(error) => {
+                                Output::Error(JavaScriptError::new(error, String::from("")))
+                            }
+                        };
+
+                        let output = rejections::report(tc, output);
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::ModuleSource(name, code, marshaling, timeout) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+
+                        // The module's namespace is handed back once its evaluation has settled.
+                        let output = match modules::evaluate(tc, &name, &code) {
+                            Some((module, result)) => {
+                                match V8Facade::to_settled_output(
+                                    Some(result),
+                                    tc,
+                                    marshaling,
+                                    timeout,
+                                ) {
+                                    Output::Result(_) => {
+                                        let namespace = module.get_module_namespace();
+
+                                        V8Facade::to_output(Some(namespace), tc, marshaling)
+                                    }
+                                    output => output,
+                                }
+                            }
+                            None => V8Facade::to_output(None, tc, marshaling),
+                        };
+
+                        let output = rejections::report(tc, output);
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::BeginSource(code, marshaling, on_complete) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = V8Facade::eval(tc, &code);
+
+                        match result {
+                            Ok(result) => {
+                                V8Facade::send_result_to_delegate(
+                                    result,
+                                    tc,
+                                    marshaling,
+                                    on_complete,
+                                );
+                            }
+
+                            Err(error) => {
+                                let error = JavaScriptError::new(error, String::from(""));
+
+                                on_complete(Output::Error(error));
+                            }
+                        }
+                    }
+
+                    Input::Function(func_args, marshaling) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = V8Facade::call_func(tc, global, func_args, marshaling.values);
+
+                        match result {
+                            Ok(result) => {
+                                V8Facade::send_result_to_output(result, tc, marshaling, &tx_out)
+                            }
+
+                            Err(error) => {
+                                let error = JavaScriptError::new(error, String::from(""));
+
+                                tx_out.send(Output::Error(error)).unwrap();
+                            }
+                        };
+                    }
+
+                    Input::BeginFunction(func_args, marshaling, on_complete) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = V8Facade::call_func(tc, global, func_args, marshaling.values);
+
+                        match result {
+                            Ok(result) => V8Facade::send_result_to_delegate(
+                                result,
+                                tc,
+                                marshaling,
+                                on_complete,
+                            ),
+
+                            Err(error) => {
+                                let error = JavaScriptError::new(error, String::from(""));
+
+                                on_complete(Output::Error(error));
+                            }
+                        };
+                    }
+
+                    Input::CheckSyntax(code, kind) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+
+                        let output = match V8Facade::check_source_syntax(tc, &code, kind) {
+                            Ok(diagnostics) => Output::SyntaxDiagnostics(diagnostics),
+                            Err(error) => {
+                                Output::Error(JavaScriptError::new(error, String::from("")))
+                            }
+                        };
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::HeapReport => {
+                        let heap_stats = &mut v8::HeapStatistics::default();
+
+                        scope.get_heap_statistics(heap_stats);
+
+                        let heap_stats = V8HeapStatistics {
+                            total_heap_size: heap_stats.total_heap_size(),
+                            total_heap_size_executable: heap_stats.total_heap_size_executable(),
+                            total_physical_size: heap_stats.total_physical_size(),
+                            total_available_size: heap_stats.total_available_size(),
+                            used_heap_size: heap_stats.used_heap_size(),
+                            heap_size_limit: heap_stats.heap_size_limit(),
+                            malloced_memory: heap_stats.malloced_memory(),
+                            does_zap_garbage: heap_stats.does_zap_garbage(),
+                            number_of_native_contexts: heap_stats.number_of_native_contexts(),
+                            number_of_detached_contexts: heap_stats.number_of_detached_contexts(),
+                            peak_malloced_memory: heap_stats.peak_malloced_memory(),
+                            used_global_handles_size: heap_stats.used_global_handles_size(),
+                            total_global_handles_size: heap_stats.total_global_handles_size(),
+                        };
+
+                        tx_out.send(Output::HeapStatistics(heap_stats)).unwrap();
+                    }
+
+                    Input::BeginHeapReport(on_complete) => {
+                        let heap_stats = &mut v8::HeapStatistics::default();
+
+                        scope.get_heap_statistics(heap_stats);
+
+                        let heap_stats = V8HeapStatistics {
+                            total_heap_size: heap_stats.total_heap_size(),
+                            total_heap_size_executable: heap_stats.total_heap_size_executable(),
+                            total_physical_size: heap_stats.total_physical_size(),
+                            total_available_size: heap_stats.total_available_size(),
+                            used_heap_size: heap_stats.used_heap_size(),
+                            heap_size_limit: heap_stats.heap_size_limit(),
+                            malloced_memory: heap_stats.malloced_memory(),
+                            does_zap_garbage: heap_stats.does_zap_garbage(),
+                            number_of_native_contexts: heap_stats.number_of_native_contexts(),
+                            number_of_detached_contexts: heap_stats.number_of_detached_contexts(),
+                            peak_malloced_memory: heap_stats.peak_malloced_memory(),
+                            used_global_handles_size: heap_stats.used_global_handles_size(),
+                            total_global_handles_size: heap_stats.total_global_handles_size(),
+                        };
+
+                        // let heap_stats = Box::into_raw(Box::new(heap_stats));
+
+                        on_complete(heap_stats);
+                    }
+
+                    Input::SetRejectionHandler(handler) => rejections::set_handler(scope, handler),
+
+                    Input::SetModuleLoader(loader) => modules::set_loader(scope, loader),
+
+                    Input::RegisterModule(specifier, code) => {
+                        modules::register(scope, &specifier, code)
+                    }
+
+                    Input::SetImportMap(json) => {
+                        let output = match modules::set_import_map(scope, &json) {
+                            Ok(()) => Output::Result(JavaScriptResult::UndefinedValue),
+                            Err(error) => {
+                                Output::Error(JavaScriptError::new(error, String::from("")))
+                            }
+                        };
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::SetMicrotaskPolicy(policy) => {
+                        scope.set_microtasks_policy(match policy {
+                            MicrotaskPolicy::Auto => v8::MicrotasksPolicy::Auto,
+                            MicrotaskPolicy::Explicit => v8::MicrotasksPolicy::Explicit,
+                        });
+                    }
+
+                    Input::MicrotaskCheckpoint => {
+                        scope.perform_microtask_checkpoint();
+
+                        let output = Output::Result(JavaScriptResult::UndefinedValue);
+                        let output = rejections::report(scope, output);
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::EnqueueMicrotask(func_args) => {
+                        let output = match V8Facade::queue_microtask(scope, global, func_args) {
+                            Ok(()) => Output::Result(JavaScriptResult::UndefinedValue),
+                            Err(error) => {
+                                Output::Error(JavaScriptError::new(error, String::from("")))
+                            }
+                        };
+
+                        tx_out.send(output).unwrap();
+                    }
+
+                    Input::LoadWasm(name, bytes, imports) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = wasm::instantiate(tc, &name, bytes, &imports);
+
+                        V8Facade::send_result_to_output(result, tc, Marshaling::default(), &tx_out);
+                    }
+
+                    Input::WasmExports(name) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = wasm::exports(tc, &name);
+
+                        V8Facade::send_result_to_output(result, tc, Marshaling::default(), &tx_out);
+                    }
+
+                    Input::WasmFunction(module, func_args) => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = V8Facade::call_wasm_export(tc, &module, func_args);
+
+                        match result {
+                            Ok(result) => V8Facade::send_result_to_output(
+                                result,
+                                tc,
+                                Marshaling::default(),
+                                &tx_out,
+                            ),
+
+                            Err(error) => {
+                                let error = JavaScriptError::new(error, String::from(""));
+
+                                tx_out.send(Output::Error(error)).unwrap();
+                            }
+                        };
+                    }
+
+                    Input::Lockdown => {
+                        let tc = &mut v8::TryCatch::new(scope);
+
+                        let result = match intrinsics::get_function(tc, "lockdown") {
+                            Some(lockdown) => lockdown.call(tc, global.into(), &[]),
+                            None => None,
+                        };
+
+                        V8Facade::send_result_to_output(result, tc, Marshaling::default(), &tx_out);
+                    }
+
+                    Input::GlobalsReport => {
+                        let tc = &mut v8::TryCatch::new(scope);
+                        let result = globals::names(tc).map(|names| names.into());
+
+                        V8Facade::send_result_to_output(result, tc, Marshaling::default(), &tx_out);
+                    }
+
+                    Input::SetTime(time) => deterministic::set_time(scope, time),
+
+                    Input::ClearTimers => timers::clear(scope),
+
+                    Input::Shutdown => {
+                        timers::clear(scope);
+                        modules::clear(scope);
+
+                        break Ok(());
+                    }
+                };
+            }
+        });
 
         Self {
             input: tx_in,
             output: rx_out,
-            handle: handle,
+            handle,
         }
     }
 
     pub fn run<S: Into<String>>(&self, source: S) -> Result<Output, String> {
+        self.run_encoded(source, ValueEncoding::Json)
+    }
+
+    pub fn run_encoded<S: Into<String>>(
+        &self,
+        source: S,
+        encoding: ValueEncoding,
+    ) -> Result<Output, String> {
+        self.input
+            .send(Input::Source(
+                SourceText::Utf8(source.into()),
+                Marshaling::new(encoding, StringEncoding::Utf8),
+            ))
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Runs source that may `await` at the top level, see `top_level_await` for how it is evaluated. Timers
+    // the result is waiting on are run as they come due, as long as they're due within `timeout`. A
+    // deterministic facade only runs the timers that are already due on its clock.
+    pub fn run_with_await<S: Into<String>>(
+        &self,
+        source: S,
+        timeout: Duration,
+    ) -> Result<Output, String> {
+        self.input
+            .send(Input::AwaitSource(
+                source.into(),
+                Marshaling::default(),
+                timeout,
+            ))
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Runs the source, then keeps running its timers until there are none left or `timeout` has passed.
+    // A promise result is settled by then, its value is handed back instead. A deterministic facade only
+    // runs the timers that are already due on its clock.
+    pub fn run_until_idle<S: Into<String>>(
+        &self,
+        source: S,
+        timeout: Duration,
+    ) -> Result<Output, String> {
+        self.input
+            .send(Input::SourceUntilIdle(
+                SourceText::Utf8(source.into()),
+                Marshaling::default(),
+                timeout,
+            ))
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Runs the source as an ES module called `name`, which is what its relative imports resolve against.
+    // Hands back the module's namespace as an object once its evaluation has settled, running the
+    // timers it's waiting on the same way as `run_with_await`.
+    pub fn run_module<N: Into<String>, S: Into<String>>(
+        &self,
+        name: N,
+        source: S,
+        timeout: Duration,
+    ) -> Result<Output, String> {
         self.input
-            .send(Input::Source(source.into()))
+            .send(Input::ModuleSource(
+                name.into(),
+                source.into(),
+                Marshaling::default(),
+                timeout,
+            ))
             .map_err(|e| format!("{:?}", e))?;
 
         self.output.recv().map_err(|e| format!("{:?}", e))
@@ -456,7 +1751,11 @@ impl V8Facade {
         on_complete: F,
     ) -> Result<(), String> {
         self.input
-            .send(Input::BeginSource(source.into(), Box::new(on_complete)))
+            .send(Input::BeginSource(
+                SourceText::Utf8(source.into()),
+                Marshaling::default(),
+                Box::new(on_complete),
+            ))
             .map_err(|e| format!("{:?}", e))?;
 
         Ok(())
@@ -467,10 +1766,54 @@ impl V8Facade {
         func_name: S,
         func_params: Vec<FunctionParameter>,
     ) -> Result<Output, String> {
-        let call_spec = Input::Function(FunctionCall {
-            name: func_name.into(),
-            arguments: func_params,
-        });
+        self.call_encoded(func_name, func_params, ValueEncoding::Json)
+    }
+
+    pub fn call_encoded<S: Into<String>>(
+        &self,
+        func_name: S,
+        func_params: Vec<FunctionParameter>,
+        encoding: ValueEncoding,
+    ) -> Result<Output, String> {
+        let call_spec = Input::Function(
+            FunctionCall {
+                name: func_name.into(),
+                arguments: func_params,
+            },
+            Marshaling::new(encoding, StringEncoding::Utf8),
+        );
+
+        self.input.send(call_spec).map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Runs UTF-16 source text and hands string results back as UTF-16, so unpaired surrogates survive
+    // the round trip.
+    pub fn run_utf16(&self, source: Vec<u16>, encoding: ValueEncoding) -> Result<Output, String> {
+        self.input
+            .send(Input::Source(
+                SourceText::Utf16(source),
+                Marshaling::new(encoding, StringEncoding::Utf16),
+            ))
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn call_utf16<S: Into<String>>(
+        &self,
+        func_name: S,
+        func_params: Vec<FunctionParameter>,
+        encoding: ValueEncoding,
+    ) -> Result<Output, String> {
+        let call_spec = Input::Function(
+            FunctionCall {
+                name: func_name.into(),
+                arguments: func_params,
+            },
+            Marshaling::new(encoding, StringEncoding::Utf16),
+        );
 
         self.input.send(call_spec).map_err(|e| format!("{:?}", e))?;
 
@@ -488,6 +1831,7 @@ impl V8Facade {
                 name: func_name.into(),
                 arguments: func_params,
             },
+            Marshaling::default(),
             Box::new(on_complete),
         );
 
@@ -496,6 +1840,25 @@ impl V8Facade {
         Ok(())
     }
 
+    // Compiles the source without running it.
+    pub fn check_syntax<S: Into<String>>(
+        &self,
+        source: S,
+        kind: SourceKind,
+    ) -> Result<Vec<SyntaxDiagnostic>, String> {
+        self.input
+            .send(Input::CheckSyntax(SourceText::Utf8(source.into()), kind))
+            .map_err(|e| format!("{:?}", e))?;
+
+        let result = self.output.recv().map_err(|e| format!("{:?}", e))?;
+
+        match result {
+            Output::SyntaxDiagnostics(diagnostics) => Ok(diagnostics),
+            Output::Error(e) => Err(e.exception),
+            _ => Err(String::from("Couldn't check the syntax...")),
+        }
+    }
+
     pub fn get_heap_statistics(&self) -> Result<V8HeapStatistics, String> {
         self.input
             .send(Input::HeapReport)
@@ -521,6 +1884,196 @@ impl V8Facade {
         Ok(())
     }
 
+    // Cancels every pending `setTimeout` and `setInterval`.
+    pub fn clear_timers(&self) -> Result<(), String> {
+        self.input
+            .send(Input::ClearTimers)
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    // Promises rejected without a handler are reported to `handler`, on the worker thread, instead of
+    // as the error for the request that rejected them. Rejections from timers that fire between
+    // requests, and what those timers throw, are only ever reported this way.
+    pub fn set_unhandled_rejection_handler<F: FnMut(JavaScriptError) + Send + 'static>(
+        &self,
+        handler: F,
+    ) -> Result<(), String> {
+        self.input
+            .send(Input::SetRejectionHandler(Some(Box::new(handler))))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    pub fn clear_unhandled_rejection_handler(&self) -> Result<(), String> {
+        self.input
+            .send(Input::SetRejectionHandler(None))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    // Modules that aren't cached yet, whether imported statically or with `import()`, are loaded by
+    // calling `loader` with their resolved specifier on the worker thread. It hands back the module's
+    // source, or `None` when there's no such module.
+    pub fn set_module_loader<F: FnMut(&str) -> Option<String> + Send + 'static>(
+        &self,
+        loader: F,
+    ) -> Result<(), String> {
+        self.input
+            .send(Input::SetModuleLoader(Some(Box::new(loader))))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    pub fn clear_module_loader(&self) -> Result<(), String> {
+        self.input
+            .send(Input::SetModuleLoader(None))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    // Makes `source` importable as `specifier`, ahead of anything the loader would hand back.
+    pub fn register_module<N: Into<String>, S: Into<String>>(
+        &self,
+        specifier: N,
+        source: S,
+    ) -> Result<(), String> {
+        self.input
+            .send(Input::RegisterModule(specifier.into(), source.into()))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    // Replaces the import map that specifiers are remapped with before they're loaded, see
+    // `modules::ImportMap` for what's supported.
+    pub fn set_import_map<S: Into<String>>(&self, json: S) -> Result<(), String> {
+        self.input
+            .send(Input::SetImportMap(json.into()))
+            .map_err(|e| format!("{:?}", e))?;
+
+        let result = self.output.recv().map_err(|e| format!("{:?}", e))?;
+
+        match result {
+            Output::Error(e) => Err(e.exception),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn set_microtask_policy(&self, policy: MicrotaskPolicy) -> Result<(), String> {
+        self.input
+            .send(Input::SetMicrotaskPolicy(policy))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
+    // Runs every queued microtask, including those they queue in turn. Rejections they leave unhandled
+    // are reported as the result, the same as for any other request.
+    pub fn perform_microtask_checkpoint(&self) -> Result<Output, String> {
+        self.input
+            .send(Input::MicrotaskCheckpoint)
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Queues a call to a global function as a microtask, it runs at the next checkpoint. Whatever it
+    // throws is reported as an unhandled rejection.
+    pub fn enqueue_microtask<S: Into<String>>(
+        &self,
+        func_name: S,
+        func_params: Vec<FunctionParameter>,
+    ) -> Result<Output, String> {
+        let call_spec = Input::EnqueueMicrotask(FunctionCall {
+            name: func_name.into(),
+            arguments: func_params,
+        });
+
+        self.input.send(call_spec).map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Compiles and instantiates a Wasm module as `name`, replacing any module loaded as `name` before.
+    // Hands back its exports as an array of `{ name, kind }`.
+    pub fn load_wasm<S: Into<String>>(
+        &self,
+        name: S,
+        bytes: Vec<u8>,
+        imports: Vec<WasmImport>,
+    ) -> Result<Output, String> {
+        self.input
+            .send(Input::LoadWasm(name.into(), bytes, imports))
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    pub fn wasm_exports<S: Into<String>>(&self, name: S) -> Result<Output, String> {
+        self.input
+            .send(Input::WasmExports(name.into()))
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Calls a function the Wasm module `name` exports. Its parameters can only be numbers and BigInts,
+    // i64 parameters take BigInts.
+    pub fn call_wasm<N: Into<String>, S: Into<String>>(
+        &self,
+        name: N,
+        func_name: S,
+        func_params: Vec<FunctionParameter>,
+    ) -> Result<Output, String> {
+        let call_spec = Input::WasmFunction(
+            name.into(),
+            FunctionCall {
+                name: func_name.into(),
+                arguments: func_params,
+            },
+        );
+
+        self.input.send(call_spec).map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Freezes the built-ins, so nothing run afterwards can tamper with them or replace the globals that
+    // hold them. Run preludes, polyfills included, before locking down. Globals the host or scripts add
+    // stay as they are.
+    pub fn lockdown(&self) -> Result<Output, String> {
+        self.input
+            .send(Input::Lockdown)
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // The names of every global scripts can see, as an array.
+    pub fn globals(&self) -> Result<Output, String> {
+        self.input
+            .send(Input::GlobalsReport)
+            .map_err(|e| format!("{:?}", e))?;
+
+        self.output.recv().map_err(|e| format!("{:?}", e))
+    }
+
+    // Moves the clock of a deterministic facade to `time`, in milliseconds since the epoch. Timers that
+    // come due run before the next input is handled.
+    pub fn set_time(&self, time: f64) -> Result<(), String> {
+        self.input
+            .send(Input::SetTime(time))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(())
+    }
+
     pub fn shutdown(&self) -> Result<(), String> {
         self.input
             .send(Input::Shutdown)
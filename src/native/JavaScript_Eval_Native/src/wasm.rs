@@ -0,0 +1,198 @@
+use std::convert::TryFrom;
+
+use crate::{
+    intrinsics,
+    modules::throw_error,
+    v8facade::{new_array_buffer, WasmImport},
+};
+
+// Wasm modules the host compiles and instantiates by name, with the `WebAssembly` built-ins captured
+// when the context was created. The instances are kept on the global behind a private key, scripts only
+// get at a module through what it imports.
+const INSTANCES_KEY: &str = "JavaScript.Eval.wasmInstances";
+
+// Has to happen before the intrinsics are captured, so the facade can't load modules either.
+pub fn disable(scope: &mut v8::HandleScope) -> Option<()> {
+    let scope = &mut v8::HandleScope::new(scope);
+
+    let global = scope.get_current_context().global(scope);
+    let name = v8::String::new(scope, "WebAssembly")?;
+
+    global.delete(scope, name.into())?;
+
+    Some(())
+}
+
+// Compiles and instantiates `bytes` as `name`, replacing whatever was loaded as `name` before. Each
+// import is the value of a global. Hands back the module's exports, see `exports`.
+pub fn instantiate<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    bytes: Vec<u8>,
+    imports: &[WasmImport],
+) -> Option<v8::Local<'s, v8::Value>> {
+    let new_module = builtin(scope, "wasmModule")?;
+    let new_instance = builtin(scope, "wasmInstance")?;
+
+    let imports = import_object(scope, imports)?;
+
+    let buffer = new_array_buffer(scope, bytes);
+    let module = new_module.new_instance(scope, &[buffer.into()])?;
+    let instance = new_instance.new_instance(scope, &[module.into(), imports.into()])?;
+
+    let record = v8::Array::new_with_elements(scope, &[module.into(), instance.into()]);
+    let key = v8::String::new(scope, name)?;
+
+    instances(scope)?.create_data_property(scope, key.into(), record.into())?;
+
+    describe_exports(scope, module.into())
+}
+
+// The module's exports as an array of `{ name, kind }`, where `kind` is one of `function`, `table`,
+// `memory` or `global`.
+pub fn exports<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let module = find(scope, name)?.get_index(scope, 0)?;
+
+    describe_exports(scope, module)
+}
+
+pub fn call<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    name: &str,
+    export: &str,
+    arguments: &[v8::Local<v8::Value>],
+) -> Option<v8::Local<'s, v8::Value>> {
+    let exports_of = builtin(scope, "wasmInstanceExports")?;
+
+    let instance = find(scope, name)?.get_index(scope, 1)?;
+    let exports = exports_of.call(scope, instance, &[])?;
+    let exports = v8::Local::<v8::Object>::try_from(exports).ok()?;
+
+    let key = v8::String::new(scope, export)?;
+    let function = exports.get(scope, key.into())?;
+
+    let function = match v8::Local::<v8::Function>::try_from(function) {
+        Ok(function) => function,
+        Err(_) => {
+            throw_error(
+                scope,
+                &format!(
+                    "The Wasm module `{}` doesn't export a function called `{}`.",
+                    name, export
+                ),
+            );
+            return None;
+        }
+    };
+
+    let receiver = v8::undefined(scope);
+
+    function.call(scope, receiver.into(), arguments)
+}
+
+fn builtin<'s>(scope: &mut v8::HandleScope<'s>, name: &str) -> Option<v8::Local<'s, v8::Function>> {
+    let function = intrinsics::get_function(scope, name);
+
+    if function.is_none() {
+        throw_error(scope, "WebAssembly is disabled.");
+    }
+
+    function
+}
+
+// Without a prototype, so module and import names like `toString` don't find anything inherited.
+fn new_dictionary<'s>(scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Object> {
+    let prototype = v8::null(scope);
+
+    v8::Object::with_prototype_and_properties(scope, prototype.into(), &[], &[])
+}
+
+fn instances<'s>(scope: &mut v8::HandleScope<'s>) -> Option<v8::Local<'s, v8::Object>> {
+    let key = v8::String::new(scope, INSTANCES_KEY)?;
+    let key = v8::Private::for_api(scope, Some(key));
+
+    let global = scope.get_current_context().global(scope);
+
+    if let Ok(instances) = v8::Local::<v8::Object>::try_from(global.get_private(scope, key)?) {
+        return Some(instances);
+    }
+
+    let instances = new_dictionary(scope);
+
+    global.set_private(scope, key, instances.into())?;
+
+    Some(instances)
+}
+
+// The module and its instance.
+fn find<'s>(scope: &mut v8::HandleScope<'s>, name: &str) -> Option<v8::Local<'s, v8::Array>> {
+    let key = v8::String::new(scope, name)?;
+    let record = instances(scope)?.get(scope, key.into())?;
+
+    match v8::Local::<v8::Array>::try_from(record) {
+        Ok(record) => Some(record),
+        Err(_) => {
+            throw_error(
+                scope,
+                &format!("There's no Wasm module loaded as `{}`.", name),
+            );
+            None
+        }
+    }
+}
+
+fn import_object<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    imports: &[WasmImport],
+) -> Option<v8::Local<'s, v8::Object>> {
+    let global = scope.get_current_context().global(scope);
+    let object = new_dictionary(scope);
+
+    for import in imports {
+        let key = v8::String::new(scope, &import.global)?;
+        let value = global.get(scope, key.into())?;
+
+        if value.is_undefined() {
+            throw_error(
+                scope,
+                &format!(
+                    "Couldn't resolve `{}` for the Wasm import `{}.{}`.",
+                    import.global, import.module, import.name
+                ),
+            );
+            return None;
+        }
+
+        let key = v8::String::new(scope, &import.module)?;
+
+        let namespace = match v8::Local::<v8::Object>::try_from(object.get(scope, key.into())?) {
+            Ok(namespace) => namespace,
+            Err(_) => {
+                let namespace = new_dictionary(scope);
+
+                object.create_data_property(scope, key.into(), namespace.into())?;
+
+                namespace
+            }
+        };
+
+        let key = v8::String::new(scope, &import.name)?;
+
+        namespace.create_data_property(scope, key.into(), value)?;
+    }
+
+    Some(object)
+}
+
+fn describe_exports<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    module: v8::Local<v8::Value>,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let module_exports = builtin(scope, "wasmModuleExports")?;
+    let receiver = v8::undefined(scope);
+
+    module_exports.call(scope, receiver.into(), &[module])
+}
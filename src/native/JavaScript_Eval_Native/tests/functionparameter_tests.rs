@@ -27,7 +27,7 @@ mod functionparameter_tests {
 
         match func_param {
             FunctionParameter::StringValue(s) => assert_eq!("Hello World", s),
-            _ => assert!(false, "Expected value wasn't returned."),
+            _ => panic!("Expected value wasn't returned."),
         }
 
         unsafe {
@@ -58,7 +58,7 @@ mod functionparameter_tests {
 
         match func_param {
             FunctionParameter::SymbolValue(s) => assert_eq!("symbol", s),
-            _ => assert!(false, "Expected value wasn't returned."),
+            _ => panic!("Expected value wasn't returned."),
         }
 
         unsafe {
@@ -89,7 +89,7 @@ mod functionparameter_tests {
 
         match func_param {
             FunctionParameter::ObjectValue(s) => assert_eq!("object", s),
-            _ => assert!(false, "Expected value wasn't returned."),
+            _ => panic!("Expected value wasn't returned."),
         }
 
         unsafe {
@@ -116,7 +116,7 @@ mod functionparameter_tests {
 
         match func_param {
             FunctionParameter::NumberValue(n) => assert_eq!(1.1, n), 
-            _ => assert!(false, "Expected value wasn't returned."),
+            _ => panic!("Expected value wasn't returned."),
         }
     }
 
@@ -139,7 +139,7 @@ mod functionparameter_tests {
 
         match func_param {
             FunctionParameter::BigIntValue(n) => assert_eq!(1, n), 
-            _ => assert!(false, "Expected value wasn't returned."),
+            _ => panic!("Expected value wasn't returned."),
         }        
     }
 
@@ -162,7 +162,7 @@ mod functionparameter_tests {
 
         match func_param {
             FunctionParameter::BoolValue(b) => assert!(b),
-            _ => assert!(false, "Expected value wasn't returned."),
+            _ => panic!("Expected value wasn't returned."),
         }
     }
 }
@@ -0,0 +1,221 @@
+#[cfg(test)]
+mod structured_clone_tests {
+    use javascript_eval_native::{
+        structured_clone::{self, ArrayBufferViewKind, CloneValue},
+        v8facade::{JavaScriptResult, Output, V8Facade, ValueEncoding},
+    };
+
+    fn run_structured(eval: &V8Facade, script: &str) -> CloneValue {
+        let result = eval
+            .run_encoded(script, ValueEncoding::StructuredClone)
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::SerializedValue(bytes)) = result {
+            structured_clone::decode(&bytes).unwrap()
+        } else {
+            panic!("Expected a serialized value.");
+        }
+    }
+
+    #[test]
+    fn it_can_decode_a_simple_object() {
+        let bytes = [0xFF, 0x0F, b'o', b'"', 0x01, b'a', b'I', 0x04, b'{', 0x01];
+
+        let value = structured_clone::decode(&bytes).unwrap();
+
+        assert_eq!(
+            CloneValue::Object {
+                id: 0,
+                properties: vec![(String::from("a"), CloneValue::Number(2.0))],
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn it_refuses_values_nested_too_deeply() {
+        let mut bytes = vec![0xFF, 0x0F];
+
+        for _ in 0..1000 {
+            bytes.extend_from_slice(&[b'o', b'"', 0x01, b'a']);
+        }
+
+        bytes.push(b'0');
+
+        for _ in 0..1000 {
+            bytes.extend_from_slice(&[b'{', 0x01]);
+        }
+
+        assert_eq!(
+            Err(String::from(
+                "Values nested more than 512 levels deep can't be decoded."
+            )),
+            structured_clone::decode(&bytes)
+        );
+    }
+
+    #[test]
+    fn it_keeps_values_json_would_lose() {
+        let eval = V8Facade::new();
+
+        let value = run_structured(
+            &eval,
+            "const d = new Date(0); ({ when: d, again: d, missing: undefined, nan: NaN, tags: new Set(['a']) });",
+        );
+
+        if let CloneValue::Object { properties, .. } = value {
+            let date_id = match &properties[0] {
+                (
+                    key,
+                    CloneValue::Date {
+                        id,
+                        epoch_milliseconds,
+                    },
+                ) => {
+                    assert_eq!("when", key);
+                    assert_eq!(0.0, *epoch_milliseconds);
+
+                    *id
+                }
+                other => panic!("Unexpected property {:?}", other),
+            };
+
+            assert_eq!(
+                (String::from("again"), CloneValue::Reference(date_id)),
+                properties[1]
+            );
+            assert_eq!(
+                (String::from("missing"), CloneValue::Undefined),
+                properties[2]
+            );
+
+            match &properties[3].1 {
+                CloneValue::Number(n) => assert!(n.is_nan()),
+                other => panic!("Unexpected value {:?}", other),
+            }
+
+            match &properties[4].1 {
+                CloneValue::Set { values, .. } => {
+                    assert_eq!(&vec![CloneValue::String(String::from("a"))], values)
+                }
+                other => panic!("Unexpected value {:?}", other),
+            }
+        } else {
+            panic!("Expected an object.");
+        }
+    }
+
+    #[test]
+    fn it_can_decode_maps_and_typed_arrays() {
+        let eval = V8Facade::new();
+
+        let value = run_structured(&eval, "new Map([[1, new Uint8Array([1, 2, 3])]]);");
+
+        if let CloneValue::Map { entries, .. } = value {
+            assert_eq!(CloneValue::Number(1.0), entries[0].0);
+
+            match &entries[0].1 {
+                CloneValue::ArrayBufferView {
+                    buffer,
+                    byte_length,
+                    ..
+                } => {
+                    assert_eq!(3, *byte_length);
+
+                    match buffer.as_ref() {
+                        CloneValue::ArrayBuffer { bytes, .. } => assert_eq!(&vec![1, 2, 3], bytes),
+                        other => panic!("Unexpected buffer {:?}", other),
+                    }
+                }
+                other => panic!("Unexpected value {:?}", other),
+            }
+        } else {
+            panic!("Expected a map.");
+        }
+    }
+
+    #[test]
+    fn it_can_decode_a_view_over_a_buffer_that_was_already_written() {
+        let bytes = [
+            0xFF, 0x0F, b'A', 0x02, b'B', 0x02, 0x01, 0x02, b'^', 0x01, b'V', b'B', 0x00, 0x02,
+            0x00, b'$', 0x00, 0x02,
+        ];
+
+        let value = structured_clone::decode(&bytes).unwrap();
+
+        assert_eq!(
+            CloneValue::Array {
+                id: 0,
+                elements: vec![
+                    CloneValue::ArrayBuffer {
+                        id: 1,
+                        bytes: vec![1, 2],
+                    },
+                    CloneValue::ArrayBufferView {
+                        id: 2,
+                        kind: ArrayBufferViewKind::Uint8Array,
+                        buffer: Box::new(CloneValue::Reference(1)),
+                        byte_offset: 0,
+                        byte_length: 2,
+                    },
+                ],
+                properties: vec![],
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn it_can_decode_a_buffer_shared_with_a_view() {
+        let eval = V8Facade::new();
+
+        let value = run_structured(
+            &eval,
+            "const buffer = new Uint8Array([1, 2]).buffer; [buffer, new Uint8Array(buffer, 1)];",
+        );
+
+        if let CloneValue::Array { elements, .. } = value {
+            let buffer_id = match &elements[0] {
+                CloneValue::ArrayBuffer { id, bytes } => {
+                    assert_eq!(&vec![1, 2], bytes);
+
+                    *id
+                }
+                other => panic!("Unexpected value {:?}", other),
+            };
+
+            match &elements[1] {
+                CloneValue::ArrayBufferView {
+                    buffer,
+                    byte_offset,
+                    byte_length,
+                    ..
+                } => {
+                    assert_eq!(&CloneValue::Reference(buffer_id), buffer.as_ref());
+                    assert_eq!(1, *byte_offset);
+                    assert_eq!(1, *byte_length);
+                }
+                other => panic!("Unexpected value {:?}", other),
+            }
+        } else {
+            panic!("Expected an array.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_when_value_cannot_be_cloned() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_encoded("(function () {});", ValueEncoding::StructuredClone)
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e
+                .exception
+                .starts_with("There was an issue serializing the result:"));
+        } else {
+            panic!("Expected an error.");
+        }
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tagged_primitive_tests {
+    use javascript_eval_native::{
+        function_parameter::FunctionParameter,
+        primitive_result::ByteBuffer,
+        tagged_primitive::{PrimitiveKind, TaggedPrimitive, TaggedValue, TAGGED_PRIMITIVE_VERSION},
+        v8facade::V8Facade,
+    };
+
+    #[test]
+    fn it_can_create_from_tagged_string_value() {
+        let mut bytes = b"a\0b".to_vec();
+
+        let primitive = TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: PrimitiveKind::String as u32,
+            value: TaggedValue {
+                buffer: ByteBuffer {
+                    data: bytes.as_mut_ptr(),
+                    length: bytes.len(),
+                },
+            },
+        };
+
+        match FunctionParameter::from_tagged(&primitive) {
+            Ok(FunctionParameter::StringValue(s)) => assert_eq!("a\0b", s),
+            _ => panic!("Expected value wasn't returned."),
+        }
+    }
+
+    #[test]
+    fn it_can_create_from_tagged_null_value() {
+        let primitive = TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: PrimitiveKind::Null as u32,
+            value: TaggedValue { number: 0.0 },
+        };
+
+        match FunctionParameter::from_tagged(&primitive) {
+            Ok(FunctionParameter::NullValue) => {}
+            _ => panic!("Expected value wasn't returned."),
+        }
+    }
+
+    #[test]
+    fn it_reads_any_non_zero_byte_as_true() {
+        for (byte, expected) in [(0, false), (1, true), (2, true)].iter() {
+            let primitive = TaggedPrimitive {
+                version: TAGGED_PRIMITIVE_VERSION,
+                kind: PrimitiveKind::Bool as u32,
+                value: TaggedValue { boolean: *byte },
+            };
+
+            match FunctionParameter::from_tagged(&primitive) {
+                Ok(FunctionParameter::BoolValue(b)) => assert_eq!(*expected, b),
+                _ => panic!("Expected value wasn't returned."),
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_utf16_strings_with_an_odd_number_of_bytes() {
+        let mut bytes = vec![0x61, 0x00, 0x62];
+
+        let primitive = TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: PrimitiveKind::Utf16String as u32,
+            value: TaggedValue {
+                buffer: ByteBuffer {
+                    data: bytes.as_mut_ptr(),
+                    length: bytes.len(),
+                },
+            },
+        };
+
+        assert!(FunctionParameter::from_tagged(&primitive).is_err());
+    }
+
+    #[test]
+    fn it_rejects_unknown_versions_and_kinds() {
+        let primitive = TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION + 1,
+            kind: PrimitiveKind::Number as u32,
+            value: TaggedValue { number: 1.0 },
+        };
+
+        assert!(FunctionParameter::from_tagged(&primitive).is_err());
+
+        let primitive = TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: 9000,
+            value: TaggedValue { number: 1.0 },
+        };
+
+        assert!(FunctionParameter::from_tagged(&primitive).is_err());
+    }
+
+    #[test]
+    fn it_refuses_handles_because_they_are_reserved() {
+        let primitive = TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: PrimitiveKind::Handle as u32,
+            value: TaggedValue { number: 0.0 },
+        };
+
+        match FunctionParameter::from_tagged(&primitive) {
+            Err(e) => assert_eq!(
+                "Handle parameters aren't supported by this version of the library.",
+                e
+            ),
+            _ => panic!("Expected an error."),
+        }
+    }
+
+    #[test]
+    fn it_tags_results_with_their_kind() {
+        let eval = V8Facade::new();
+
+        let cases = [
+            ("undefined;", PrimitiveKind::Undefined),
+            ("null;", PrimitiveKind::Null),
+            ("1.5;", PrimitiveKind::Number),
+            ("'hello';", PrimitiveKind::String),
+            ("[1];", PrimitiveKind::Array),
+            ("throw new Error('nope');", PrimitiveKind::Error),
+            ("Symbol('x');", PrimitiveKind::Symbol),
+            ("(a) => a;", PrimitiveKind::Function),
+            ("new Date(0);", PrimitiveKind::Date),
+            ("/x/g;", PrimitiveKind::RegExp),
+        ];
+
+        for (script, kind) in cases.iter() {
+            let result = eval.run(*script).unwrap();
+            let result = TaggedPrimitive::from_output(result);
+
+            assert_eq!(TAGGED_PRIMITIVE_VERSION, result.version);
+            assert_eq!(*kind as u32, result.kind);
+
+            unsafe {
+                TaggedPrimitive::free_raw(result.into_raw());
+            }
+        }
+    }
+}
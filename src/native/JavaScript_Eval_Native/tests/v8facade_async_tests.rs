@@ -11,10 +11,10 @@ mod v8facade_async_tests {
                 if let JavaScriptResult::NumberValue(n) = r {
                     assert_eq!(2.0, n);
                 } else {
-                    assert!(false, "Wrong answer.");
+                    panic!("Wrong answer.");
                 }
             } else {
-                assert!(false, "Welp.");
+                panic!("Welp.");
             }
         })
         .unwrap();
@@ -31,7 +31,7 @@ mod v8facade_async_tests {
             if let Output::Error(r) = result {
                 assert_eq!("Hello from the error!", r.exception);
             } else {
-                assert!(false, "Welp.");
+                panic!("Welp.");
             }
         })
         .unwrap();
@@ -51,10 +51,10 @@ mod v8facade_async_tests {
                     if let JavaScriptResult::StringValue(s) = r {
                         assert_eq!("hello world", s);
                     } else {
-                        assert!(false, "Wrong.");
+                        panic!("Wrong.");
                     }
                 } else {
-                    assert!(false, "Welp.");
+                    panic!("Welp.");
                 } 
             },
         )
@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod v8facade_await_tests {
+    use std::time::Duration;
+
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, V8Facade};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn it_returns_last_expression_after_await() {
+        let eval = V8Facade::new();
+
+        let cases = [
+            ("await Promise.resolve(1); 40 + 2;", 42.0),
+            (
+                "const a = await Promise.resolve(5)\nconst b = 2\na\n-b",
+                3.0,
+            ),
+            ("const o = { a: await 7 }.a; o // the answer", 7.0),
+            (
+                "let total = 0;\nfor (const n of [1, 2, 3]) { total += await n; }\ntotal;",
+                6.0,
+            ),
+        ];
+
+        for (script, expected) in cases.iter() {
+            let result = eval.run_with_await(*script, TIMEOUT).unwrap();
+
+            if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+                assert_eq!(*expected, n);
+            } else {
+                panic!("Welp.");
+            }
+        }
+    }
+
+    #[test]
+    fn it_returns_undefined_when_last_statement_is_not_an_expression() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_with_await("const config = await Promise.resolve({});", TIMEOUT)
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::UndefinedValue) = result {
+        } else {
+            panic!("Welp.");
+        }
+
+        // Only statements after the last `}` are tried, this one still runs but its value is lost.
+        let result = eval
+            .run_with_await("answer = { a: await 41 }.a + 1", TIMEOUT)
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::UndefinedValue) = result {
+        } else {
+            panic!("Welp.");
+        }
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = eval.run("answer;").unwrap() {
+            assert_eq!(42.0, n);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_declares_top_level_vars_and_functions_as_globals() {
+        let eval = V8Facade::new();
+
+        eval.run_with_await(
+            "var answer = await Promise.resolve(40), { step } = { step: 1 };\nfunction increment(n) { return n + step; }\nlet hidden = 1;",
+            TIMEOUT,
+        )
+        .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) =
+            eval.run("increment(increment(answer));").unwrap()
+        {
+            assert_eq!(42.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) =
+            eval.run("typeof hidden;").unwrap()
+        {
+            assert_eq!("undefined", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_when_awaited_promise_rejects_or_never_settles() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_with_await("await Promise.reject(new RangeError('nope'));", TIMEOUT)
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("RangeError: nope", e.exception);
+            assert_eq!("RangeError", e.name);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run_with_await("await new Promise(() => {});", TIMEOUT)
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!(
+                "The script is still awaiting a promise that nothing is left to settle.",
+                e.exception
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_runs_the_timers_an_await_is_waiting_on() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_with_await(
+                "await new Promise(r => setTimeout(r, 0)); await new Promise(r => setTimeout(() => r(42), 10));",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(42.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        // Timers it isn't waiting on are left for later.
+        let result = eval
+            .run_with_await(
+                "setInterval(() => {}, 1); await new Promise(r => setTimeout(r, 5));",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::UndefinedValue) = result {
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run_with_await(
+                "await new Promise(r => setTimeout(r, 60000));",
+                Duration::from_millis(50),
+            )
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!(
+                "The script was still waiting on timers after 50 ms.",
+                e.exception
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod v8facade_binary_tests {
+    use std::ffi::{CStr, CString};
+
+    use javascript_eval_native::{
+        exec, free_primitive_result, free_v8,
+        function_parameter::FunctionParameter,
+        get_v8,
+        v8facade::{JavaScriptResult, Output, V8Facade},
+    };
+
+    #[test]
+    fn it_can_pass_uint8array_and_get_binary_result() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("function invert(bytes) { return bytes.map(b => 255 - b); }")
+            .unwrap();
+
+        let result = eval
+            .call(
+                "invert",
+                vec![FunctionParameter::Uint8ArrayValue(vec![0, 1, 255])],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::BinaryValue { bytes, .. }) = result {
+            assert_eq!(vec![255, 254, 0], bytes);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_pass_array_buffer() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("function describe(buffer) { return `${buffer instanceof ArrayBuffer}:${buffer.byteLength}`; }")
+            .unwrap();
+
+        let result = eval
+            .call(
+                "describe",
+                vec![FunctionParameter::ArrayBufferValue(vec![1, 2, 3, 4])],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("true:4", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_only_the_bytes_a_view_covers() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("new Uint8Array([1, 2, 3, 4, 5]).subarray(1, 3);")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::BinaryValue { bytes, .. }) = result {
+            assert_eq!(vec![2, 3], bytes);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("new Uint16Array([258]).buffer;").unwrap();
+
+        if let Output::Result(JavaScriptResult::BinaryValue { bytes, .. }) = result {
+            assert_eq!(vec![2, 1], bytes);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_still_hands_binary_results_to_exec_as_objects() {
+        unsafe {
+            let eval = get_v8();
+
+            let script = CString::new("new Uint8Array([1, 2]);").unwrap();
+            let result = exec(eval, script.as_ptr());
+
+            assert_eq!(
+                r#"{"0":1,"1":2}"#,
+                CStr::from_ptr((*result).object_value).to_str().unwrap()
+            );
+
+            let binary_value = &*(*result).binary_value;
+            assert_eq!(
+                &[1, 2],
+                std::slice::from_raw_parts(binary_value.data, binary_value.length)
+            );
+
+            free_primitive_result(result);
+            free_v8(eval);
+        }
+    }
+}
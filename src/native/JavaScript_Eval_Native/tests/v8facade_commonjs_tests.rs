@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod v8facade_commonjs_tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, V8Facade, V8FacadeOptions};
+
+    // A fresh directory per test, with `root` inside it so there's something outside the root to try
+    // and reach.
+    fn sandbox(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let sandbox = std::env::temp_dir().join(format!(
+            "javascript_eval_commonjs_{}_{}",
+            name,
+            std::process::id()
+        ));
+
+        let _ = fs::remove_dir_all(&sandbox);
+
+        for (path, contents) in files {
+            let path = sandbox.join(path);
+
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        sandbox
+    }
+
+    fn eval_in(sandbox: &Path) -> V8Facade {
+        V8Facade::with_options(V8FacadeOptions {
+            commonjs_root: Some(sandbox.join("root")),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn it_requires_files_packages_and_json() {
+        let sandbox = sandbox(
+            "packages",
+            &[
+                ("root/lib/math.js", "exports.add = (a, b) => a + b;"),
+                ("root/lib/where.js", "module.exports = __filename + ' ' + __dirname;"),
+                (
+                    "root/node_modules/greet/package.json",
+                    r#"{"main": "src/greet.js"}"#,
+                ),
+                (
+                    "root/node_modules/greet/src/greet.js",
+                    "const data = require('../data.json'); module.exports = name => data.greeting + ', ' + name;",
+                ),
+                ("root/node_modules/greet/data.json", r#"{"greeting": "Hello"}"#),
+            ],
+        );
+
+        let eval = eval_in(&sandbox);
+
+        let result = eval
+            .run("const { add } = require('./lib/math'); [require('greet')('world'), add(1, 2), require('./lib/math') === require('/lib/math.js'), require('./lib/where')].join();")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("Hello, world,3,true,/lib/where.js /lib", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_never_reads_outside_the_root() {
+        let sandbox = sandbox(
+            "escape",
+            &[
+                ("secret.js", "module.exports = 'secret';"),
+                ("root/index.js", "module.exports = 'root';"),
+            ],
+        );
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(sandbox.join("secret.js"), sandbox.join("root/link.js"))
+            .unwrap();
+
+        let eval = eval_in(&sandbox);
+
+        for specifier in &["../secret.js", "/../../secret", "./link.js"] {
+            let result = eval.run(format!("require('{}');", specifier)).unwrap();
+
+            if let Output::Error(e) = result {
+                assert_eq!(
+                    format!("Error: Cannot find module '{}'", specifier),
+                    e.exception
+                );
+            } else {
+                panic!("Welp.");
+            }
+        }
+
+        let result = eval.run("require('..');").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("root", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_leaves_require_out_by_default() {
+        let eval = V8Facade::new();
+
+        let result = eval.run("typeof require;").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("undefined", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod v8facade_deterministic_tests {
+    use std::{thread, time::Duration};
+
+    use javascript_eval_native::v8facade::{
+        DeterministicOptions, JavaScriptResult, Output, V8Facade, V8FacadeOptions,
+    };
+
+    fn deterministic(random_seed: u64, locale: &str) -> V8Facade {
+        V8Facade::with_options(V8FacadeOptions {
+            deterministic: Some(DeterministicOptions {
+                random_seed,
+                time: 1_700_000_000_000.0,
+                locale: String::from(locale),
+            }),
+            ..Default::default()
+        })
+    }
+
+    fn run_to_string(eval: &V8Facade, source: &str) -> String {
+        if let Output::Result(JavaScriptResult::StringValue(s)) = eval.run(source).unwrap() {
+            s
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    const RANDOM: &str = "[Math.random(), Math.random(), Math.random()].join();";
+
+    #[test]
+    fn it_gives_the_same_output_for_the_same_seed() {
+        let first = run_to_string(&deterministic(42, "en-US"), RANDOM);
+        let second = run_to_string(&deterministic(42, "en-US"), RANDOM);
+        let other = run_to_string(&deterministic(7, "en-US"), RANDOM);
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn it_reads_the_time_from_the_host_clock_in_utc() {
+        let eval = deterministic(0, "en-US");
+
+        assert_eq!(
+            "1700000000000,Tue Nov 14 2023 22:13:20 GMT+0000 (Coordinated Universal Time),0,3",
+            run_to_string(
+                &eval,
+                "[Date.now(), new Date(), new Date().getTimezoneOffset(), new Date('2020-01-02T03:04:05').getHours()].join();"
+            )
+        );
+
+        eval.set_time(86_400_000.0).unwrap();
+
+        assert_eq!(
+            "1970-01-02T00:00:00.000Z",
+            run_to_string(&eval, "new Date().toISOString();")
+        );
+    }
+
+    #[test]
+    fn it_parses_and_formats_local_time_as_utc() {
+        let eval = deterministic(0, "en-US");
+
+        assert_eq!(
+            "1577934245000,1577930645000,1577923200000,1577923200000",
+            run_to_string(
+                &eval,
+                "[Date.parse('2020-01-02T03:04:05'), Date.parse('2020-01-02T03:04:05+01:00'), Date.parse('2020-01-02'), new Date(2020, 0, 2).getTime()].join();"
+            )
+        );
+
+        assert_eq!(
+            "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)|Fri Jan 02 1970 00:00:00 GMT+0000 (Coordinated Universal Time)|UTC",
+            run_to_string(
+                &eval,
+                "[new Date(0).toString(), String(new Date(86400000)), Intl.DateTimeFormat().resolvedOptions().timeZone].join('|');"
+            )
+        );
+    }
+
+    #[test]
+    fn it_uses_the_locale_by_default() {
+        let eval = deterministic(0, "de-DE");
+
+        assert_eq!(
+            "1.234,5|1.1.1970, 00:00:00|de-DE",
+            run_to_string(
+                &eval,
+                "[(1234.5).toLocaleString(), new Date(0).toLocaleString(), new Intl.NumberFormat().resolvedOptions().locale].join('|');"
+            )
+        );
+    }
+
+    #[test]
+    fn it_runs_timers_when_the_host_moves_the_clock() {
+        let eval = deterministic(0, "en-US");
+
+        let _ = eval
+            .run("var fired = []; setTimeout(() => fired.push(Date.now()), 10); setTimeout(() => fired.push('now'), 0);")
+            .unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+
+        assert_eq!("now", run_to_string(&eval, "fired.join();"));
+
+        eval.set_time(1_700_000_000_009.0).unwrap();
+
+        assert_eq!("now", run_to_string(&eval, "fired.join();"));
+
+        eval.set_time(1_700_000_000_010.0).unwrap();
+
+        assert_eq!("now,1700000000010", run_to_string(&eval, "fired.join();"));
+    }
+}
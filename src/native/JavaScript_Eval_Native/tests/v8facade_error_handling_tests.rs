@@ -1,6 +1,12 @@
 #[cfg(test)]
 mod v8facade_error_handling_tests {
-    use javascript_eval_native::v8facade::{Output, V8Facade};
+    use std::ffi::CStr;
+
+    use javascript_eval_native::{
+        function_parameter::FunctionParameter,
+        primitive_result::UnsafeJavaScriptError,
+        v8facade::{JavaScriptResult, Output, V8Facade},
+    };
 
     #[test]
     fn it_gets_error_with_bad_function_call() {
@@ -14,7 +20,7 @@ mod v8facade_error_handling_tests {
                 e.exception
             );
         } else {
-            assert!(false, "I guess no error was thrown...");
+            panic!("I guess no error was thrown...");
         }
     }
 
@@ -29,7 +35,217 @@ mod v8facade_error_handling_tests {
             assert_eq!("", e.stack_trace);
             assert_eq!("There was an issue compiling the provided script: SyntaxError: Unexpected token '{'", e.exception);
         } else {
-            assert!(false, "I guess no error was throw...");
+            panic!("I guess no error was throw...");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_when_result_is_circular() {
+        let eval = V8Facade::new();
+        let result = eval.run("const o = {}; o.self = o; o;").unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e
+                .exception
+                .starts_with("There was an issue marshaling the result: TypeError"));
+        } else {
+            panic!("I guess no error was thrown...");
+        }
+
+        // The worker thread is still around to take more work.
+        let result = eval.run("1+1;").unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(2.0, n);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_when_result_contains_bigint_or_throwing_to_json() {
+        let eval = V8Facade::new();
+
+        for script in &[
+            "({ big: 1n });",
+            "({ toJSON() { throw new Error('nope'); } });",
+        ] {
+            let result = eval.run(*script).unwrap();
+
+            if let Output::Error(e) = result {
+                assert!(e
+                    .exception
+                    .starts_with("There was an issue marshaling the result:"));
+            } else {
+                panic!("I guess no error was thrown...");
+            }
+        }
+    }
+
+    #[test]
+    fn it_ignores_reassigned_global_json() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("JSON = undefined; function echo(val) { return val; }")
+            .unwrap();
+
+        let result = eval
+            .call(
+                "echo",
+                vec![FunctionParameter::ObjectValue(String::from("{\"a\":1}"))],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(s)) = result {
+            assert_eq!("{\"a\":1}", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_with_bad_json_parameter() {
+        let eval = V8Facade::new();
+
+        let _ = eval.run("function echo(val) { return val; }").unwrap();
+
+        let result = eval
+            .call(
+                "echo",
+                vec![FunctionParameter::ObjectValue(String::from("{nope"))],
+            )
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e
+                .exception
+                .starts_with("There was an issue parsing the provided JSON:"));
+        } else {
+            panic!("I guess no error was thrown...");
+        }
+    }
+
+    #[test]
+    fn it_gets_stack_trace_when_non_error_is_thrown() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("function throwMessage(message) {\n    throw message;\n}")
+            .unwrap();
+
+        let result = eval.run("throwMessage('Hello from the error!');").unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Hello from the error!", e.exception);
+            assert_eq!(
+                "Hello from the error!\n    at throwMessage (<anonymous>:2:5)\n    at <anonymous>:1:1",
+                e.stack_trace
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_cause_chain() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("const root = new TypeError('root'); throw new Error('outer', { cause: new RangeError('middle', { cause: root }) });")
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Error", e.name);
+            assert_eq!("outer", e.message);
+
+            let middle = e.cause.unwrap();
+            assert_eq!("RangeError", middle.name);
+            assert_eq!("middle", middle.message);
+            assert!(middle.stack_trace.starts_with("RangeError: middle\n    at"));
+
+            let root = middle.cause.unwrap();
+            assert_eq!("TypeError", root.name);
+            assert!(root.cause.is_none());
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_aggregate_error_details() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("const e = new AggregateError([new Error('first'), 'second'], 'both failed'); e.cause = e; throw e;")
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("AggregateError", e.name);
+            assert_eq!("both failed", e.message);
+            assert!(e.cause.is_none());
+            assert_eq!(2, e.errors.len());
+            assert_eq!("first", e.errors[0].message);
+            assert_eq!("second", e.errors[1].exception);
+
+            let unsafe_error = UnsafeJavaScriptError::from_error(e).into_raw();
+
+            unsafe {
+                assert_eq!(2, (*unsafe_error).errors_length);
+
+                let first = &*(*unsafe_error).errors;
+                assert_eq!("first", CStr::from_ptr(first.message).to_str().unwrap());
+
+                UnsafeJavaScriptError::free_raw(unsafe_error);
+            }
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_custom_error_properties() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("throw Object.assign(new Error('bad'), { code: 'E_VALIDATION', field: 'email' });")
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Error", e.name);
+            assert_eq!("bad", e.message);
+            assert_eq!(
+                Some(String::from(r#"{"code":"E_VALIDATION","field":"email"}"#)),
+                e.properties
+            );
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run("throw { name: 'ValidationError', message: 'bad', code: 42 };")
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("ValidationError", e.name);
+            assert_eq!("bad", e.message);
+            assert_eq!(
+                Some(String::from(
+                    r#"{"name":"ValidationError","message":"bad","code":42}"#
+                )),
+                e.properties
+            );
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("throw 'just a string';").unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("", e.name);
+            assert!(e.properties.is_none());
+        } else {
+            panic!("Welp.");
         }
     }
 }
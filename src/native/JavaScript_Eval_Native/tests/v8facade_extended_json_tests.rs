@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod v8facade_extended_json_tests {
+    use javascript_eval_native::{
+        function_parameter::FunctionParameter,
+        v8facade::{JavaScriptResult, Output, V8Facade, ValueEncoding},
+    };
+
+    #[test]
+    fn it_tags_values_json_cant_represent() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_encoded(
+                "({ big: 12345678901234567890123n, nan: NaN, inf: -Infinity, when: new Date(0), missing: undefined, list: [undefined, 1] });",
+                ValueEncoding::ExtendedJson,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(json)) = result {
+            assert_eq!(
+                r#"{"big":{"$bigint":"12345678901234567890123"},"nan":{"$number":"NaN"},"inf":{"$number":"-Infinity"},"when":{"$date":0},"missing":{"$undefined":true},"list":[{"$undefined":true},1]}"#,
+                json
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_reads_tagged_values_from_parameters() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("function describe(o) { return [typeof o.big, o.big === 2n ** 70n, Number.isNaN(o.nan), o.when instanceof Date, 'missing' in o, o.missing].join(); }")
+            .unwrap();
+
+        let result = eval
+            .call_encoded(
+                "describe",
+                vec![FunctionParameter::ObjectValue(String::from(
+                    r#"{"big":{"$bigint":"1180591620717411303424"},"nan":{"$number":"NaN"},"when":{"$date":0},"missing":{"$undefined":true}}"#,
+                ))],
+                ValueEncoding::ExtendedJson,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("bigint,true,true,true,true,", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_reports_bad_tags_and_cycles() {
+        let eval = V8Facade::new();
+
+        let _ = eval.run("function identity(o) { return o; }").unwrap();
+
+        let result = eval
+            .call_encoded(
+                "identity",
+                vec![FunctionParameter::ObjectValue(String::from(
+                    r#"{"$bigint":"12x"}"#,
+                ))],
+                ValueEncoding::ExtendedJson,
+            )
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e
+                .exception
+                .starts_with("There was an issue parsing the provided JSON:"));
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run_encoded("const o = {}; o.self = o; o;", ValueEncoding::ExtendedJson)
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!(
+                "There was an issue marshaling the result: TypeError: Converting circular structure to extended JSON",
+                e.exception
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+}
@@ -0,0 +1,25 @@
+// V8 is initialized once per process, so this is the only test in its binary.
+#[cfg(test)]
+mod v8facade_initialization_tests {
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, V8Facade};
+
+    #[test]
+    fn it_initializes_v8_with_flags_once() {
+        V8Facade::initialize("--expose-gc", 2).unwrap();
+
+        let eval = V8Facade::new();
+
+        let result = eval.run("typeof gc;").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("function", s);
+        } else {
+            panic!("Welp.");
+        }
+
+        assert_eq!(
+            Err(String::from("V8 has already been initialized.")),
+            V8Facade::initialize("", 0)
+        );
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod v8facade_microtask_tests {
+    use javascript_eval_native::{
+        function_parameter::FunctionParameter,
+        v8facade::{JavaScriptResult, MicrotaskPolicy, Output, V8Facade},
+    };
+
+    #[test]
+    fn it_holds_microtasks_until_a_checkpoint() {
+        let eval = V8Facade::new();
+
+        eval.set_microtask_policy(MicrotaskPolicy::Explicit)
+            .unwrap();
+
+        let _ = eval
+            .run("var seen = []; Promise.resolve().then(() => seen.push('then'));")
+            .unwrap();
+
+        let result = eval.run("seen.length;").unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(0.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.perform_microtask_checkpoint().unwrap();
+
+        assert!(matches!(
+            result,
+            Output::Result(JavaScriptResult::UndefinedValue)
+        ));
+
+        let result = eval.run("seen.join();").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("then", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_runs_microtasks_the_host_enqueued() {
+        let eval = V8Facade::new();
+
+        eval.set_microtask_policy(MicrotaskPolicy::Explicit)
+            .unwrap();
+
+        let _ = eval
+            .run("var calls = []; function record(x) { calls.push(x); } Promise.resolve().then(() => record('promise'));")
+            .unwrap();
+
+        let _ = eval
+            .enqueue_microtask("record", vec![FunctionParameter::NumberValue(1.0)])
+            .unwrap();
+
+        let _ = eval.perform_microtask_checkpoint().unwrap();
+
+        let result = eval.run("calls.join();").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("promise,1", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_from_an_enqueued_microtask() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("function fail() { throw new Error('boom'); }")
+            .unwrap();
+
+        let result = eval.enqueue_microtask("missing", vec![]).unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Couldn't resolve function `missing`.", e.exception);
+        } else {
+            panic!("Welp.");
+        }
+
+        let _ = eval.enqueue_microtask("fail", vec![]).unwrap();
+
+        let result = eval.perform_microtask_checkpoint().unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Uncaught (in promise) Error: boom", e.exception);
+        } else {
+            panic!("Welp.");
+        }
+    }
+}
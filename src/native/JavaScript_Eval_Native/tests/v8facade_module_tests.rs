@@ -0,0 +1,226 @@
+#[cfg(test)]
+mod v8facade_module_tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, V8Facade};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn it_loads_static_imports_through_the_loader() {
+        let eval = V8Facade::new();
+
+        eval.set_module_loader(|specifier| match specifier {
+            "lib/math.js" => Some(String::from("export const add = (a, b) => a + b;")),
+            _ => None,
+        })
+        .unwrap();
+
+        let result = eval
+            .run_module(
+                "app/main.js",
+                "import { add } from '../lib/math.js'; export const sum = add(1, 2);",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(json)) = result {
+            assert_eq!(r#"{"sum":3}"#, json);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_shares_modules_between_static_and_dynamic_imports() {
+        let eval = V8Facade::new();
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = loads.clone();
+
+        eval.set_module_loader(move |specifier| {
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            match specifier {
+                "counter.js" => Some(String::from(
+                    "export const evaluations = (globalThis.evaluations = (globalThis.evaluations || 0) + 1);",
+                )),
+                _ => None,
+            }
+        })
+        .unwrap();
+
+        let _ = eval
+            .run_module("main.js", "import './counter.js';", TIMEOUT)
+            .unwrap();
+
+        let result = eval
+            .run_with_await(
+                "const m = await import('./counter.js'); m.evaluations;",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(1.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        assert_eq!(1, loads.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn it_rejects_dynamic_import_of_missing_module() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_with_await("await import('./missing.js');", TIMEOUT)
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Error: Cannot find module './missing.js'", e.exception);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run_with_await(
+                "await import('./missing.js').catch(e => 'caught');",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("caught", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_runs_the_timers_a_module_is_waiting_on() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_module(
+                "main.js",
+                "export const answer = await new Promise(r => setTimeout(() => r(42), 10));",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(json)) = result {
+            assert_eq!(r#"{"answer":42}"#, json);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_resolves_registered_modules_through_the_import_map() {
+        let eval = V8Facade::new();
+
+        eval.set_module_loader(|_| Some(String::from("export const from = 'loader';")))
+            .unwrap();
+
+        eval.register_module("lib/math", "export const add = (a, b) => a + b;")
+            .unwrap();
+        eval.register_module(
+            "lib/utils/twice.js",
+            "import { add } from 'math'; export const twice = x => add(x, x);",
+        )
+        .unwrap();
+
+        eval.set_import_map(r#"{"imports": {"math": "lib/math", "utils/": "lib/utils/"}}"#)
+            .unwrap();
+
+        let result = eval
+            .run_module(
+                "main.js",
+                "import { twice } from 'utils/twice.js'; const other = await import('other.js'); export const n = twice(21) + other.from;",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(json)) = result {
+            assert_eq!(r#"{"n":"42loader"}"#, json);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_for_bad_import_map() {
+        let eval = V8Facade::new();
+
+        let result = eval.set_import_map(r#"{"imports": {"math": 1}}"#);
+
+        assert_eq!(
+            Err(String::from(
+                "The import map's address for `math` must be a string."
+            )),
+            result
+        );
+    }
+
+    #[test]
+    fn it_imports_json_and_text_modules() {
+        let eval = V8Facade::new();
+
+        eval.register_module("config.json", r#"{"port": 8080}"#)
+            .unwrap();
+        eval.register_module("banner.txt", "hello").unwrap();
+
+        let result = eval
+            .run_module(
+                "main.js",
+                "import config from './config.json' assert { type: 'json' }; import banner from './banner.txt' assert { type: 'text' }; const again = await import('./config.json', { assert: { type: 'json' } }); export const result = [config.port, banner, again.default === config].join();",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(json)) = result {
+            assert_eq!(r#"{"result":"8080,hello,true"}"#, json);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run_module(
+                "css.js",
+                "import styles from './banner.txt' assert { type: 'css' };",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!(
+                "TypeError: Import assertion type \"css\" is unsupported.",
+                e.exception
+            );
+        } else {
+            panic!("Welp.");
+        }
+
+        // Import attributes are read as the assertions V8 11.0 parses.
+        let result = eval
+            .run_module(
+                "attributes.js",
+                "import config from './config.json' with { type: 'json' };\nexport { default as banner } from './banner.txt' with { type: 'text' };\nexport const port = config.port;",
+                TIMEOUT,
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::ObjectValue(json)) = result {
+            assert_eq!(r#"{"banner":"hello","port":8080}"#, json);
+        } else {
+            panic!("Welp.");
+        }
+    }
+}
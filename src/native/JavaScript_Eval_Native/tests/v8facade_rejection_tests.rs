@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod v8facade_rejection_tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, V8Facade};
+
+    #[test]
+    fn it_gets_error_for_unhandled_rejection() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("Promise.reject(new RangeError('lost')); 1;")
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Uncaught (in promise) RangeError: lost", e.exception);
+            assert_eq!("RangeError", e.name);
+            assert!(e.stack_trace.starts_with("RangeError: lost\n    at "));
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_every_unhandled_rejection_in_a_request() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("Promise.reject(new RangeError('first')); Promise.reject(new TypeError('second')); 1;")
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!(
+                "Uncaught (in promise) AggregateError: 2 promises were rejected and not handled.",
+                e.exception
+            );
+            assert_eq!("AggregateError", e.name);
+
+            let inner: Vec<&str> = e.errors.iter().map(|e| e.exception.as_str()).collect();
+
+            assert_eq!(
+                vec![
+                    "Uncaught (in promise) RangeError: first",
+                    "Uncaught (in promise) TypeError: second"
+                ],
+                inner
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_ignores_rejections_handled_in_the_same_request() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("const p = Promise.reject(1); p.catch(() => {}); 2;")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(2.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .run_with_await("await Promise.reject('nope');", Duration::from_secs(5))
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("nope", e.exception);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_reports_rejections_to_a_handler() {
+        let eval = V8Facade::new();
+        let (tx, rx) = mpsc::channel();
+
+        eval.set_unhandled_rejection_handler(move |e| tx.send(e).unwrap())
+            .unwrap();
+
+        let result = eval
+            .run("function reject() { Promise.reject('nope'); }\nreject(); 3;")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(3.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        let e = rx.try_recv().unwrap();
+
+        assert_eq!("Uncaught (in promise) nope", e.exception);
+        assert!(e
+            .stack_trace
+            .starts_with("Uncaught (in promise) nope\n    at reject (<anonymous>:1:"));
+
+        eval.clear_unhandled_rejection_handler().unwrap();
+
+        let result = eval.run("Promise.reject('again'); 4;").unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!("Uncaught (in promise) again", e.exception);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_reports_what_timers_between_requests_throw_to_the_handler() {
+        let eval = V8Facade::new();
+        let (tx, rx) = mpsc::channel();
+
+        eval.set_unhandled_rejection_handler(move |e| tx.send(e).unwrap())
+            .unwrap();
+
+        let _ = eval
+            .run("setTimeout(() => { Promise.reject('nope'); throw new TypeError('boom'); }, 1);")
+            .unwrap();
+
+        let thrown = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let rejected = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert_eq!("TypeError: boom", thrown.exception);
+        assert_eq!("Uncaught (in promise) nope", rejected.exception);
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod v8facade_sandbox_tests {
+    use javascript_eval_native::{
+        function_parameter::FunctionParameter,
+        v8facade::{HostGlobal, JavaScriptResult, Output, V8Facade, V8FacadeOptions},
+    };
+
+    #[test]
+    fn it_disallows_code_generation_from_strings() {
+        let eval = V8Facade::with_options(V8FacadeOptions {
+            disallow_code_generation: true,
+            ..Default::default()
+        });
+
+        for source in &[
+            "eval('1 + 1');",
+            "new Function('return 1;')();",
+            "(function () {}).constructor('return 1;')();",
+        ] {
+            let result = eval.run(*source).unwrap();
+
+            if let Output::Error(e) = result {
+                assert!(e.exception.starts_with("EvalError: "));
+            } else {
+                panic!("Welp.");
+            }
+        }
+
+        let _ = eval.run("function add(a, b) { return a + b; }").unwrap();
+
+        let result = eval
+            .call(
+                "add",
+                vec![
+                    FunctionParameter::NumberValue(1.0),
+                    FunctionParameter::NumberValue(2.0),
+                ],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(3.0, n);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_allows_code_generation_from_strings_by_default() {
+        let eval = V8Facade::new();
+
+        let result = eval.run("eval('1 + 1');").unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(2.0, n);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_keeps_scripts_from_tampering_with_built_ins_after_lockdown() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("Array.prototype.last = function () { return this[this.length - 1]; };")
+            .unwrap();
+
+        let result = eval.lockdown().unwrap();
+
+        assert!(matches!(
+            result,
+            Output::Result(JavaScriptResult::UndefinedValue)
+        ));
+
+        let _ = eval
+            .run("Array.prototype.push = null; JSON = null; Object.prototype.polluted = true;")
+            .unwrap();
+
+        let result = eval
+            .run("const seen = []; seen.push([1, 2, 3].last(), typeof JSON.parse, ({}).polluted); JSON.stringify(seen);")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!(r#"[3,"function",null]"#, s);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("'use strict'; Math.max = () => 0;").unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e.exception.starts_with("TypeError: "));
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_still_lets_objects_override_inherited_properties_after_lockdown() {
+        let eval = V8Facade::new();
+
+        let _ = eval.lockdown().unwrap();
+
+        let result = eval
+            .run("'use strict'; function Point() {} Point.prototype.toString = () => 'point'; const e = new Error('boom'); e.name = 'Custom'; [String(new Point()), String(e)].join();")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("point,Custom: boom", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    // The names in a report of the globals, sorted.
+    fn global_names(eval: &V8Facade) -> Vec<String> {
+        if let Output::Result(JavaScriptResult::ArrayValue(json)) = eval.globals().unwrap() {
+            let mut names: Vec<String> = json
+                .trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .map(|name| name.trim_matches('"').to_string())
+                .collect();
+
+            names.sort();
+
+            names
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_keeps_only_allowed_globals_and_adds_host_globals() {
+        let eval = V8Facade::with_options(V8FacadeOptions {
+            allowed_globals: Some(vec![
+                String::from("Object"),
+                String::from("JSON"),
+                String::from("setTimeout"),
+            ]),
+            host_globals: vec![
+                HostGlobal {
+                    name: String::from("config"),
+                    json: String::from(r#"{"port": 8080}"#),
+                },
+                HostGlobal {
+                    name: String::from("broken"),
+                    json: String::from("{"),
+                },
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            vec![
+                "Infinity",
+                "JSON",
+                "NaN",
+                "Object",
+                "broken",
+                "config",
+                "setTimeout",
+                "undefined"
+            ],
+            global_names(&eval)
+        );
+
+        let result = eval
+            .run("[typeof WebAssembly, typeof eval, typeof Array, config.port].join();")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("undefined,undefined,undefined,8080", s);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("broken;").unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e
+                .exception
+                .starts_with("Error: The host global `broken` isn't valid JSON: SyntaxError: "));
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_keeps_every_global_by_default() {
+        let eval = V8Facade::new();
+
+        let names = global_names(&eval);
+
+        for name in &["Array", "WebAssembly", "eval", "globalThis", "setTimeout"] {
+            assert!(names.contains(&name.to_string()));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod v8facade_string_tests {
+    use std::slice;
+
+    use javascript_eval_native::{
+        call_utf16, call_utf8, free_primitive_result, free_v8,
+        function_parameter::FunctionParameter,
+        get_v8,
+        primitive_result::{ByteBuffer, PrimitiveResult},
+        tagged_primitive::{PrimitiveKind, TaggedPrimitive, TaggedValue, TAGGED_PRIMITIVE_VERSION},
+        v8facade::{JavaScriptResult, Output, V8Facade, ValueEncoding},
+    };
+
+    #[test]
+    fn it_keeps_unpaired_surrogates_in_utf16_mode() {
+        let eval = V8Facade::new();
+
+        let mut source: Vec<u16> = "'a".encode_utf16().collect();
+        source.push(0xD800);
+        source.extend("';".encode_utf16());
+
+        let result = eval.run_utf16(source, ValueEncoding::Json).unwrap();
+
+        if let Output::Result(JavaScriptResult::Utf16StringValue(s)) = result {
+            assert_eq!(vec![0x61, 0xD800], s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_pass_utf16_parameters() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("function codes(s) { return Array.from(s, c => c.charCodeAt(0)).join(); }")
+            .unwrap();
+
+        let result = eval
+            .call(
+                "codes",
+                vec![FunctionParameter::Utf16StringValue(vec![0xDC00, 0x62])],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("56320,98", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_return_strings_with_embedded_nuls() {
+        let eval = V8Facade::new();
+
+        let result = eval.run("'a\\u0000b';").unwrap();
+
+        let result = PrimitiveResult::from_output(result).into_raw();
+
+        unsafe {
+            let bytes = slice::from_raw_parts(
+                (*result).string_value as *const u8,
+                (*result).string_value_length,
+            );
+
+            assert_eq!(b"a\0b", bytes);
+
+            PrimitiveResult::free_raw(result);
+        }
+    }
+
+    fn string_parameter(kind: PrimitiveKind, bytes: &mut Vec<u8>) -> TaggedPrimitive {
+        TaggedPrimitive {
+            version: TAGGED_PRIMITIVE_VERSION,
+            kind: kind as u32,
+            value: TaggedValue {
+                buffer: ByteBuffer {
+                    data: bytes.as_mut_ptr(),
+                    length: bytes.len(),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn it_passes_utf8_parameters_with_embedded_nuls_through_ffi() {
+        let name = "codes";
+        let mut bytes = b"a\0b".to_vec();
+        let parameters = [string_parameter(PrimitiveKind::String, &mut bytes)];
+
+        unsafe {
+            let eval = get_v8();
+
+            let _ = (*eval)
+                .run("function codes(s) { return Array.from(s, c => c.charCodeAt(0)).join(); }")
+                .unwrap();
+
+            let result = call_utf8(
+                eval,
+                name.as_ptr(),
+                name.len(),
+                parameters.as_ptr(),
+                parameters.len(),
+            );
+
+            let bytes = slice::from_raw_parts(
+                (*result).string_value as *const u8,
+                (*result).string_value_length,
+            );
+
+            assert_eq!(b"97,0,98", bytes);
+
+            free_primitive_result(result);
+            free_v8(eval);
+        }
+    }
+
+    #[test]
+    fn it_passes_utf16_parameters_with_unpaired_surrogates_through_ffi() {
+        let name: Vec<u16> = "identity".encode_utf16().collect();
+        let mut bytes = 0xD800u16.to_le_bytes().to_vec();
+        let parameters = [string_parameter(PrimitiveKind::Utf16String, &mut bytes)];
+
+        unsafe {
+            let eval = get_v8();
+
+            let _ = (*eval).run("function identity(s) { return s; }").unwrap();
+
+            let result = call_utf16(
+                eval,
+                name.as_ptr(),
+                name.len(),
+                parameters.as_ptr(),
+                parameters.len(),
+            );
+
+            let units = slice::from_raw_parts(
+                (*result).utf16_string_value,
+                (*result).utf16_string_value_length,
+            );
+
+            assert_eq!(&[0xD800], units);
+
+            free_primitive_result(result);
+            free_v8(eval);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod v8facade_syntax_tests {
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, SourceKind, V8Facade};
+
+    #[test]
+    fn it_checks_syntax_without_running() {
+        let eval = V8Facade::new();
+
+        let diagnostics = eval
+            .check_syntax("var ran = true;", SourceKind::Script)
+            .unwrap();
+
+        assert!(diagnostics.is_empty());
+
+        let result = eval.run("typeof ran;").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("undefined", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_reports_syntax_error_positions() {
+        let eval = V8Facade::new();
+
+        let diagnostics = eval
+            .check_syntax("let a = 1;\nlet b = {;", SourceKind::Script)
+            .unwrap();
+
+        assert_eq!(1, diagnostics.len());
+
+        let diagnostic = &diagnostics[0];
+
+        assert_eq!("SyntaxError: Unexpected token ';'", diagnostic.message);
+        assert_eq!(2, diagnostic.line);
+        assert_eq!(10, diagnostic.column);
+        assert_eq!(20, diagnostic.start_position);
+        assert_eq!(21, diagnostic.end_position);
+        assert_eq!("let b = {;", diagnostic.source_line);
+    }
+
+    #[test]
+    fn it_checks_modules_as_modules() {
+        let eval = V8Facade::new();
+
+        let source = "import { thing } from './thing.js';\nexport default thing;";
+
+        let diagnostics = eval.check_syntax(source, SourceKind::Script).unwrap();
+        assert_eq!(1, diagnostics.len());
+
+        let diagnostics = eval.check_syntax(source, SourceKind::Module).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod v8facade_tests {
+    use std::ffi::{CStr, CString};
+
     use javascript_eval_native::{
+        exec, free_primitive_result, free_v8,
         function_parameter::FunctionParameter,
+        get_v8,
         v8facade::{JavaScriptResult, Output, V8Facade},
     };
 
@@ -14,10 +18,10 @@ mod v8facade_tests {
             if let JavaScriptResult::NumberValue(n) = r {
                 assert_eq!(2.0, n);
             } else {
-                assert!(false, "Wrong answer.");
+                panic!("Wrong answer.");
             }
         } else {
-            assert!(false, "Welp.");
+            panic!("Welp.");
         }
     }
 
@@ -35,7 +39,7 @@ mod v8facade_tests {
         if let Output::Error(r) = result {
             assert_eq!("Hello from the error!", r.exception);
         } else {
-            assert!(false, "Welp.");
+            panic!("Welp.");
         }
     }
 
@@ -55,10 +59,10 @@ mod v8facade_tests {
             if let JavaScriptResult::StringValue(s) = r {
                 assert_eq!("hello world", s);
             } else {
-                assert!(false, "Wrong.");
+                panic!("Wrong.");
             }
         } else {
-            assert!(false, "Welp.");
+            panic!("Welp.");
         }
     }
 
@@ -90,10 +94,127 @@ mod v8facade_tests {
             if let JavaScriptResult::ArrayValue(s) = r {
                 assert_eq!("[1,2,3]", s);
             } else {
-                assert!(false, "Wrong.");
+                panic!("Wrong.");
             }
         } else {
-            assert!(false, "Welp.");
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_describe_symbol_results() {
+        let eval = V8Facade::new();
+
+        let result = eval.run("Symbol('tag');").unwrap();
+
+        if let Output::Result(JavaScriptResult::SymbolValue(d)) = result {
+            assert_eq!(Some(String::from("tag")), d);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("Symbol();").unwrap();
+
+        if let Output::Result(JavaScriptResult::SymbolValue(d)) = result {
+            assert_eq!(None, d);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_describe_function_results() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run("function add(a, b) { return a + b; } add.toString = () => 'nope'; add;")
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::FunctionValue {
+            name,
+            arity,
+            source,
+        }) = result
+        {
+            assert_eq!("add", name);
+            assert_eq!(2, arity);
+            assert_eq!("function add(a, b) { return a + b; }", source);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("class Point {}; Point;").unwrap();
+
+        if let Output::Result(JavaScriptResult::FunctionValue { name, source, .. }) = result {
+            assert_eq!("Point", name);
+            assert_eq!("class Point {}", source);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_describe_date_and_regexp_results() {
+        let eval = V8Facade::new();
+
+        let result = eval.run("new Date(86400000);").unwrap();
+
+        if let Output::Result(JavaScriptResult::DateValue {
+            epoch_milliseconds, ..
+        }) = result
+        {
+            assert_eq!(86400000.0, epoch_milliseconds);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval.run("/a+b/gi;").unwrap();
+
+        if let Output::Result(JavaScriptResult::RegExpValue { pattern, flags, .. }) = result {
+            assert_eq!("a+b", pattern);
+            assert_eq!("gi", flags);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_still_hands_dates_and_regexps_to_exec_as_objects() {
+        let cases = [
+            ("new Date(86400000)", r#""1970-01-02T00:00:00.000Z""#),
+            ("new Date(NaN)", "null"),
+            ("/a+b/gi", "{}"),
+            ("Object.assign(/a+b/gi, { note: 1 })", r#"{"note":1}"#),
+        ];
+
+        unsafe {
+            let eval = get_v8();
+
+            for (expression, expected) in cases.iter() {
+                let script = CString::new(format!("JSON.stringify({});", expression)).unwrap();
+                let result = exec(eval, script.as_ptr());
+
+                assert_eq!(
+                    *expected,
+                    CStr::from_ptr((*result).string_value).to_str().unwrap()
+                );
+
+                free_primitive_result(result);
+
+                let script = CString::new(format!("{};", expression)).unwrap();
+                let result = exec(eval, script.as_ptr());
+
+                assert!((*result).string_value.is_null());
+                assert!(!(*result).number_value_set);
+                assert_eq!(
+                    *expected,
+                    CStr::from_ptr((*result).object_value).to_str().unwrap()
+                );
+
+                free_primitive_result(result);
+            }
+
+            free_v8(eval);
         }
     }
 }
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod v8facade_timer_tests {
+    use std::{thread, time::Duration};
+
+    use javascript_eval_native::v8facade::{JavaScriptResult, Output, V8Facade};
+
+    #[test]
+    fn it_runs_timers_until_idle() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_until_idle(
+                "const order = []; setTimeout(() => order.push('b'), 20); setTimeout((x) => order.push(x), 10, 'a'); new Promise(r => setTimeout(() => r(order.join()), 30));",
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("a,b", s);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_can_clear_an_interval_from_its_callback() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_until_idle(
+                "let ticks = 0; const id = setInterval(() => { if (++ticks === 3) clearInterval(id); }, 1); new Promise(r => setTimeout(() => r(ticks), 50));",
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(3.0, n);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_error_when_timers_outlast_the_timeout() {
+        let eval = V8Facade::new();
+
+        let result = eval
+            .run_until_idle("setTimeout(() => {}, 60000);", Duration::from_millis(50))
+            .unwrap();
+
+        if let Output::Error(e) = result {
+            assert_eq!(
+                "The script was still waiting on timers after 50 ms.",
+                e.exception
+            );
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_runs_timers_between_inputs_and_can_clear_them() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("setTimeout(() => { globalThis.fired = true; }, 10);")
+            .unwrap();
+
+        thread::sleep(Duration::from_millis(100));
+
+        let result = eval.run("globalThis.fired === true;").unwrap();
+
+        if let Output::Result(JavaScriptResult::BoolValue(b)) = result {
+            assert!(b);
+        } else {
+            panic!("Welp.");
+        }
+
+        let _ = eval
+            .run("setTimeout(() => { globalThis.cleared = false; }, 10);")
+            .unwrap();
+
+        eval.clear_timers().unwrap();
+
+        thread::sleep(Duration::from_millis(100));
+
+        let result = eval.run("globalThis.cleared === undefined;").unwrap();
+
+        if let Output::Result(JavaScriptResult::BoolValue(b)) = result {
+            assert!(b);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_runs_due_timers_between_back_to_back_inputs() {
+        let eval = V8Facade::new();
+
+        let _ = eval
+            .run("var ticks = []; setTimeout(() => ticks.push('timer'), 5);")
+            .unwrap();
+
+        // Queued all at once, so the worker never runs out of inputs while they're handled.
+        for i in 0..20 {
+            eval.begin_run(
+                format!(
+                    "const end{0} = Date.now() + 2; while (Date.now() < end{0}) {{}} ticks.push({0});",
+                    i
+                ),
+                |_| {},
+            )
+            .unwrap();
+        }
+
+        let result = eval.run("ticks.indexOf('timer');").unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert!((0.0..20.0).contains(&n));
+        } else {
+            panic!("Welp.");
+        }
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod v8facade_wasm_tests {
+    use javascript_eval_native::{
+        function_parameter::FunctionParameter,
+        v8facade::{JavaScriptResult, Output, V8Facade, V8FacadeOptions, WasmImport},
+    };
+
+    // (import "env" "double" (func (param i32) (result i32)))
+    // (func (export "add") (param i32 i32) (result i32) ...)
+    // (func (export "square") (param i64) (result i64) ...)
+    // (func (export "quadruple") (param i32) (result i32) local.get 0 call 0 call 0)
+    // (memory (export "memory") 1)
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // Header
+        0x01, 0x11, 0x03, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f, 0x60, 0x01, 0x7e, 0x01, 0x7e, 0x60,
+        0x01, 0x7f, 0x01, 0x7f, // Types
+        0x02, 0x0e, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x06, 0x64, 0x6f, 0x75, 0x62, 0x6c, 0x65, 0x00,
+        0x02, // Imports
+        0x03, 0x04, 0x03, 0x00, 0x01, 0x02, // Functions
+        0x05, 0x03, 0x01, 0x00, 0x01, // Memory
+        0x07, 0x25, 0x04, 0x03, 0x61, 0x64, 0x64, 0x00, 0x01, 0x06, 0x73, 0x71, 0x75, 0x61, 0x72,
+        0x65, 0x00, 0x02, 0x09, 0x71, 0x75, 0x61, 0x64, 0x72, 0x75, 0x70, 0x6c, 0x65, 0x00, 0x03,
+        0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x00, // Exports
+        0x0a, 0x1a, 0x03, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x07, 0x00, 0x20, 0x00,
+        0x20, 0x00, 0x7e, 0x0b, 0x08, 0x00, 0x20, 0x00, 0x10, 0x00, 0x10, 0x00, 0x0b, // Code
+    ];
+
+    const EXPORTS: &str = r#"[{"name":"add","kind":"function"},{"name":"square","kind":"function"},{"name":"quadruple","kind":"function"},{"name":"memory","kind":"memory"}]"#;
+
+    fn load(eval: &V8Facade) -> Output {
+        let _ = eval.run("function double(x) { return x * 2; }").unwrap();
+
+        eval.load_wasm(
+            "math",
+            MODULE.to_vec(),
+            vec![WasmImport {
+                module: String::from("env"),
+                name: String::from("double"),
+                global: String::from("double"),
+            }],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_loads_wasm_and_calls_its_exports() {
+        let eval = V8Facade::new();
+
+        if let Output::Result(JavaScriptResult::ArrayValue(json)) = load(&eval) {
+            assert_eq!(EXPORTS, json);
+        } else {
+            panic!("Welp.");
+        }
+
+        if let Output::Result(JavaScriptResult::ArrayValue(json)) =
+            eval.wasm_exports("math").unwrap()
+        {
+            assert_eq!(EXPORTS, json);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .call_wasm(
+                "math",
+                "add",
+                vec![
+                    FunctionParameter::NumberValue(2.0),
+                    FunctionParameter::NumberValue(3.0),
+                ],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(5.0, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .call_wasm("math", "square", vec![FunctionParameter::BigIntValue(12)])
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::BigIntValue(n)) = result {
+            assert_eq!(144, n);
+        } else {
+            panic!("Welp.");
+        }
+
+        let result = eval
+            .call_wasm(
+                "math",
+                "quadruple",
+                vec![FunctionParameter::NumberValue(5.0)],
+            )
+            .unwrap();
+
+        if let Output::Result(JavaScriptResult::NumberValue(n)) = result {
+            assert_eq!(20.0, n);
+        } else {
+            panic!("Welp.");
+        }
+    }
+
+    #[test]
+    fn it_gets_errors_for_bad_wasm_calls() {
+        let eval = V8Facade::new();
+
+        let result = eval.load_wasm("math", MODULE.to_vec(), vec![]).unwrap();
+
+        if let Output::Error(e) = result {
+            assert!(e.exception.starts_with("TypeError: WebAssembly.Instance()"));
+        } else {
+            panic!("Welp.");
+        }
+
+        let _ = load(&eval);
+
+        let failures = vec![
+            (
+                "math",
+                "add",
+                vec![FunctionParameter::StringValue(String::from("1"))],
+                "Only numbers and BigInts can be passed to Wasm functions.",
+            ),
+            (
+                "math",
+                "memory",
+                vec![],
+                "Error: The Wasm module `math` doesn't export a function called `memory`.",
+            ),
+            (
+                "missing",
+                "add",
+                vec![],
+                "Error: There's no Wasm module loaded as `missing`.",
+            ),
+        ];
+
+        for (module, func_name, params, expected) in failures {
+            if let Output::Error(e) = eval.call_wasm(module, func_name, params).unwrap() {
+                assert_eq!(expected, e.exception);
+            } else {
+                panic!("Welp.");
+            }
+        }
+    }
+
+    #[test]
+    fn it_can_disable_wasm() {
+        let eval = V8Facade::with_options(V8FacadeOptions {
+            disable_wasm: true,
+            ..Default::default()
+        });
+
+        let result = eval.run("typeof WebAssembly;").unwrap();
+
+        if let Output::Result(JavaScriptResult::StringValue(s)) = result {
+            assert_eq!("undefined", s);
+        } else {
+            panic!("Welp.");
+        }
+
+        if let Output::Error(e) = load(&eval) {
+            assert_eq!("Error: WebAssembly is disabled.", e.exception);
+        } else {
+            panic!("Welp.");
+        }
+    }
+}